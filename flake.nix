@@ -28,6 +28,9 @@
           pango
           gdk-pixbuf
           graphene
+          # Widget mode (`[window] mode = "widget"`), loaded at runtime
+          gtk4-layer-shell
+          xorg.libX11
         ];
 
         # Native build inputs for compilation
@@ -77,6 +80,12 @@
 
           inherit buildInputs nativeBuildInputs;
 
+          # Widget mode loads these at runtime, so keep them findable
+          postFixup = ''
+            patchelf --add-rpath "${pkgs.lib.makeLibraryPath [ pkgs.gtk4-layer-shell pkgs.xorg.libX11 ]}" \
+              $out/bin/chronomatrix
+          '';
+
           meta = with pkgs.lib; {
             description = "Digital clock where each digit is made of analog clocks";
             homepage = "https://github.com/yourusername/chronomatrix";
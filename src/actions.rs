@@ -0,0 +1,288 @@
+//! Application actions for controlling a running instance.
+//!
+//! Every operation a user or script can trigger is registered as a `gio`
+//! action on the application, so the same code path serves the command line
+//! of a second instance, D-Bus (`gapplication action`) and anything else
+//! that can activate `app.*` actions.
+//!
+//! | Action                  | Parameter | Effect                                  |
+//! | ----------------------- | --------- | --------------------------------------- |
+//! | `app.set-mode`          | string    | A mode, e.g. `stopwatch` or `ambient`   |
+//! | `app.start-timer`       | string    | Start a countdown, e.g. `5m` or `1:30`  |
+//! | `app.toggle-running`    | -         | Start or pause the timer or stopwatch   |
+//! | `app.reset`             | -         | Reset the timer or stopwatch            |
+//! | `app.show-message`      | string    | Scroll a message across the display     |
+//! | `app.reload-config`     | -         | Reload the configuration files          |
+//! | `app.toggle-fullscreen` | -         | Enter or leave fullscreen               |
+//! | `app.zoom-in`           | -         | Make the clocks larger                  |
+//! | `app.zoom-out`          | -         | Make the clocks smaller                 |
+//! | `app.set-theme`         | string    | Switch to a theme, e.g. `nord`          |
+//! | `app.cycle-theme`       | -         | Switch to the next theme                |
+//! | `app.preferences`       | -         | Open the preferences dialog             |
+//! | `app.quit`              | -         | Quit the application                    |
+//!
+//! Keyboard shortcuts and the right-click menu activate these same actions
+//! (see `controls`).
+
+use gtk4::prelude::*;
+use gtk4::{Application, gio, glib};
+
+use crate::AppContext;
+use crate::config::DisplayMode;
+use crate::mode::parse_duration;
+use crate::theme;
+
+/// Change in clock size per zoom step, in pixels.
+const ZOOM_STEP: i64 = 4;
+
+/// Smallest clock size zooming out reaches, in pixels.
+const MIN_ZOOM_SIZE: i64 = 8;
+
+/// Registers all `app.*` actions on the application.
+///
+/// # Arguments
+/// * `app` - The application to add the actions to
+/// * `ctx` - Shared handles to the window and clock display
+pub fn register(app: &Application, ctx: &AppContext) {
+    let clock_display = &ctx.clock_display;
+    let window = &ctx.window;
+
+    let set_mode = gio::ActionEntry::builder("set-mode")
+        .parameter_type(Some(glib::VariantTy::STRING))
+        .activate(glib::clone!(
+            #[strong]
+            clock_display,
+            move |_: &Application, _, param| {
+                let Some(name) = param.and_then(|p| p.get::<String>()) else {
+                    return;
+                };
+                match name.parse::<DisplayMode>() {
+                    Ok(mode) => clock_display.borrow().set_mode(mode),
+                    Err(e) => eprintln!("Warning: {}", e),
+                }
+            }
+        ))
+        .build();
+
+    let start_timer = gio::ActionEntry::builder("start-timer")
+        .parameter_type(Some(glib::VariantTy::STRING))
+        .activate(glib::clone!(
+            #[strong]
+            clock_display,
+            move |_: &Application, _, param| {
+                let Some(text) = param.and_then(|p| p.get::<String>()) else {
+                    return;
+                };
+                match parse_duration(&text) {
+                    Ok(duration) => clock_display.borrow().start_timer(duration),
+                    Err(e) => eprintln!("Warning: {}", e),
+                }
+            }
+        ))
+        .build();
+
+    let toggle_running = gio::ActionEntry::builder("toggle-running")
+        .activate(glib::clone!(
+            #[strong]
+            clock_display,
+            move |_: &Application, _, _| {
+                clock_display.borrow().toggle_running();
+            }
+        ))
+        .build();
+
+    let reset = gio::ActionEntry::builder("reset")
+        .activate(glib::clone!(
+            #[strong]
+            clock_display,
+            move |_: &Application, _, _| {
+                clock_display.borrow().reset_mode();
+            }
+        ))
+        .build();
+
+    let show_message = gio::ActionEntry::builder("show-message")
+        .parameter_type(Some(glib::VariantTy::STRING))
+        .activate(glib::clone!(
+            #[strong]
+            clock_display,
+            move |_: &Application, _, param| {
+                if let Some(text) = param.and_then(|p| p.get::<String>()) {
+                    clock_display.borrow().show_message(&text);
+                }
+            }
+        ))
+        .build();
+
+    let reload_config = gio::ActionEntry::builder("reload-config")
+        .activate(glib::clone!(
+            #[strong]
+            ctx,
+            move |_: &Application, _, _| ctx.request_reload()
+        ))
+        .build();
+
+    let toggle_fullscreen = gio::ActionEntry::builder("toggle-fullscreen")
+        .activate(glib::clone!(
+            #[weak]
+            window,
+            move |_: &Application, _, _| {
+                if window.is_fullscreen() {
+                    window.unfullscreen();
+                } else {
+                    window.fullscreen();
+                }
+            }
+        ))
+        .build();
+
+    let zoom_in = gio::ActionEntry::builder("zoom-in")
+        .activate(glib::clone!(
+            #[strong]
+            ctx,
+            move |_: &Application, _, _| zoom(&ctx, ZOOM_STEP)
+        ))
+        .build();
+
+    let zoom_out = gio::ActionEntry::builder("zoom-out")
+        .activate(glib::clone!(
+            #[strong]
+            ctx,
+            move |_: &Application, _, _| zoom(&ctx, -ZOOM_STEP)
+        ))
+        .build();
+
+    let set_theme = gio::ActionEntry::builder("set-theme")
+        .parameter_type(Some(glib::VariantTy::STRING))
+        .activate(glib::clone!(
+            #[strong]
+            ctx,
+            move |_: &Application, _, param| {
+                if let Some(name) = param.and_then(|p| p.get::<String>())
+                    && let Err(e) = set_theme_override(&ctx, &name)
+                {
+                    eprintln!("Warning: {}", e);
+                }
+            }
+        ))
+        .build();
+
+    let cycle_theme = gio::ActionEntry::builder("cycle-theme")
+        .activate(glib::clone!(
+            #[strong]
+            ctx,
+            move |_: &Application, _, _| cycle_theme(&ctx)
+        ))
+        .build();
+
+    let preferences = gio::ActionEntry::builder("preferences")
+        .activate(glib::clone!(
+            #[strong]
+            ctx,
+            move |_: &Application, _, _| crate::preferences::present(&ctx)
+        ))
+        .build();
+
+    let quit = gio::ActionEntry::builder("quit")
+        .activate(|app: &Application, _, _| app.quit())
+        .build();
+
+    app.add_action_entries([
+        set_mode,
+        start_timer,
+        toggle_running,
+        reset,
+        show_message,
+        reload_config,
+        toggle_fullscreen,
+        zoom_in,
+        zoom_out,
+        set_theme,
+        cycle_theme,
+        preferences,
+        quit,
+    ]);
+}
+
+/// Resizes the clocks by overriding `clock.size` until the next restart.
+///
+/// Steps build on a pending override, so repeated presses add up before
+/// the reload applies them.
+///
+/// # Arguments
+/// * `ctx` - Shared handles to the config source and reload flag
+/// * `step` - Pixels to add to the clock size (negative to shrink)
+fn zoom(ctx: &AppContext, step: i64) {
+    let mut source = ctx.source.borrow_mut();
+    let current = source
+        .overrides
+        .get("clock")
+        .and_then(|clock| clock.get("size"))
+        .and_then(toml::Value::as_integer)
+        .unwrap_or_else(|| i64::from(ctx.config.borrow().clock.size));
+
+    let size = (current + step).max(MIN_ZOOM_SIZE);
+    if size != current {
+        source.set("clock", "size", toml::Value::Integer(size));
+        ctx.request_reload();
+    }
+}
+
+/// Switches to a theme by overriding `theme.name` until the next restart.
+///
+/// Picking a theme also stops following the system style until then. The
+/// reload cross-fades to the new colors.
+///
+/// # Arguments
+/// * `ctx` - Shared handles to the config source and reload flag
+/// * `name` - Name of a built-in or user theme
+///
+/// # Returns
+/// An error naming the available themes if there is no such theme, in
+/// which case the current theme stays
+pub fn set_theme_override(ctx: &AppContext, name: &str) -> Result<(), String> {
+    let themes_dir = themes_dir(ctx);
+    if theme::find(name, &themes_dir).is_none() {
+        return Err(format!(
+            "Unknown theme '{}' (available: {})",
+            name,
+            theme::names(&themes_dir).join(", ")
+        ));
+    }
+    let mut source = ctx.source.borrow_mut();
+    source.set("theme", "name", toml::Value::String(name.to_string()));
+    source.set("theme", "follow_system", toml::Value::Boolean(false));
+    ctx.request_reload();
+    Ok(())
+}
+
+/// Switches to the theme after the current one, like `zoom` building on a
+/// pending override so repeated presses step through the list.
+fn cycle_theme(ctx: &AppContext) {
+    let current = {
+        let source = ctx.source.borrow();
+        source
+            .overrides
+            .get("theme")
+            .and_then(|theme| theme.get("name"))
+            .and_then(toml::Value::as_str)
+            .map(str::to_string)
+            .unwrap_or_else(|| {
+                let config = ctx.config.borrow();
+                config.theme.selected(&source.system).1.to_string()
+            })
+    };
+
+    let names = theme::names(&themes_dir(ctx));
+    if let Some(next) = theme::next(&current, &names)
+        && let Err(e) = set_theme_override(ctx, next)
+    {
+        eprintln!("Warning: {}", e);
+    }
+}
+
+/// Returns the user themes directory next to the main config file.
+fn themes_dir(ctx: &AppContext) -> std::path::PathBuf {
+    let path = ctx.source.borrow().path();
+    theme::themes_dir(path.parent().unwrap_or(std::path::Path::new(".")))
+}
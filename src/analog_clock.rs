@@ -9,6 +9,15 @@
 //! - **Cumulative angle tracking**: Ensures hands always rotate clockwise, never backwards
 //! - **Active/inactive states**: Different colors for clocks forming digits vs background clocks
 //! - **Color transitions**: Smooth color interpolation during state changes
+//! - **Face styles**: Circle, square, rounded square or no face, with tick
+//!   marks and bevel shading, separately for the active and inactive states
+//! - **Hand styles**: Separate length, width, color, caps, taper and
+//!   counterweight for the hour and minute hands
+//! - **Effects**: Optional glow around active hands and drop shadow under all
+//!   hands, drawn as several widening translucent strokes, and a motion trail
+//!   of fading copies behind sweeping hands
+//! - **Live restyling**: Colors, stroke width and animation speed can be changed
+//!   in place; new colors cross-fade from the old ones
 //!
 //! # Animation System
 //! When `set_angles()` is called, the clock:
@@ -30,7 +39,12 @@ use gtk4::subclass::prelude::*;
 use gtk4::{DrawingArea, glib};
 use std::cell::RefCell;
 use std::f64::consts::PI;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+
+use crate::config::{
+    Bevel, EffectsConfig, Face, FaceConfig, FaceStyle, Hand, HandCap, HandStyle, HandsConfig,
+    TickMarks,
+};
 
 /// Inactive clock position - hour hand at 135° (SE diagonal)
 const INACTIVE_HOUR_ANGLE: i32 = 135;
@@ -48,32 +62,222 @@ const ALT_INACTIVE_MINUTE_ANGLE: i32 = 225;
 const ANIMATION_FPS: u64 = 60;
 
 /// Animation frame duration in milliseconds (16ms = ~60 FPS)
-const FRAME_DURATION_MS: u64 = 1000 / ANIMATION_FPS;
+pub const FRAME_DURATION_MS: u64 = 1000 / ANIMATION_FPS;
 
 /// Angle offset to make 0° point to 12 o'clock instead of 3 o'clock
 const ANGLE_OFFSET_DEGREES: f64 = -90.0;
 
-/// Clock face border width in pixels
-const CLOCK_BORDER_WIDTH: f64 = 1.0;
-
 /// Radius reduction for clock face (creates padding inside border)
 const CLOCK_RADIUS_PADDING: f64 = 2.0;
 
+/// Corner radius of a rounded square face, relative to its half width
+const ROUNDED_SQUARE_CORNER: f64 = 0.35;
+
+/// Distance of tick marks from the edge of the face in pixels
+const TICK_INSET: f64 = 1.5;
+
+/// Tick mark width in pixels
+const TICK_WIDTH: f64 = 1.0;
+
+/// Length of the 12, 3, 6 and 9 o'clock tick marks, relative to the radius
+const QUARTER_TICK_LENGTH: f64 = 0.2;
+
+/// Length of the other hour tick marks, relative to the radius
+const HOUR_TICK_LENGTH: f64 = 0.1;
+
+/// Opacity of the dark side of a bevel
+const BEVEL_SHADE_ALPHA: f64 = 0.35;
+
+/// Opacity of the light side of a raised bevel
+const BEVEL_LIGHT_ALPHA: f64 = 0.15;
+
 /// Hand length reduction from clock radius (creates gap at edge)
 const HAND_LENGTH_REDUCTION: f64 = 5.0;
 
+/// Radius of a hand's counterweight disc relative to the hand's width
+const COUNTERWEIGHT_RADIUS: f64 = 1.0;
+
+/// Number of widening strokes that make up a glow or soft shadow
+const EFFECT_PASSES: u32 = 4;
+
+/// Hand speed in degrees per frame at which a motion trail is fully opaque
+const TRAIL_FULL_SPEED: f64 = 12.0;
+
 /// Center dot opacity when clock is active
 const CENTER_DOT_OPACITY_ACTIVE: f64 = 0.5;
 
 /// Center dot opacity when clock is inactive
 const CENTER_DOT_OPACITY_INACTIVE: f64 = 1.0;
 
+/// Duration of the cross-fade when colors are changed (e.g. on config reload)
+pub const COLOR_FADE_DURATION_MS: u64 = 500;
+
+/// Faces of a clock while forming a digit and while resting.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ClockFaces {
+    pub active: Face,
+    pub inactive: Face,
+}
+
+impl ClockFaces {
+    /// Builds the faces from the `[face]` section.
+    pub fn from_config(config: &FaceConfig) -> Self {
+        ClockFaces {
+            active: config.face(true),
+            inactive: config.face(false),
+        }
+    }
+}
+
+/// Colors of one hand while forming a digit and while resting.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HandRgba {
+    pub active: (f64, f64, f64, f64),
+    pub inactive: (f64, f64, f64, f64),
+}
+
+impl HandRgba {
+    /// Returns the color of one state.
+    fn state(&self, active: bool) -> (f64, f64, f64, f64) {
+        if active { self.active } else { self.inactive }
+    }
+
+    fn lerp(&self, to: &HandRgba, t: f64) -> HandRgba {
+        HandRgba {
+            active: lerp_color(self.active, to.active, t),
+            inactive: lerp_color(self.inactive, to.inactive, t),
+        }
+    }
+}
+
+/// How a clock looks: its size, hands, colors, faces and effects.
 #[derive(Debug, Clone)]
+pub struct ClockStyle {
+    /// Clock diameter in pixels
+    pub size: i32,
+    /// Width of clock hands in pixels
+    pub stroke_width: f64,
+    pub hands: HandsConfig,
+    pub colors: ClockColors,
+    pub faces: ClockFaces,
+    pub effects: EffectsConfig,
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct ClockColors {
-    pub active_color: (f64, f64, f64, f64),
-    pub inactive_color: (f64, f64, f64, f64),
+    pub hour: HandRgba,
+    pub minute: HandRgba,
     pub bg_color: (f64, f64, f64, f64),
     pub border_color: (f64, f64, f64, f64),
+    pub shadow_color: (f64, f64, f64, f64),
+}
+
+impl ClockColors {
+    /// Blends every color towards `to` by `t` (0.0 = self, 1.0 = `to`).
+    fn lerp(&self, to: &ClockColors, t: f64) -> ClockColors {
+        ClockColors {
+            hour: self.hour.lerp(&to.hour, t),
+            minute: self.minute.lerp(&to.minute, t),
+            bg_color: lerp_color(self.bg_color, to.bg_color, t),
+            border_color: lerp_color(self.border_color, to.border_color, t),
+            shadow_color: lerp_color(self.shadow_color, to.shadow_color, t),
+        }
+    }
+
+    /// Returns the colors of one hand.
+    fn hand(&self, hand: Hand) -> &HandRgba {
+        match hand {
+            Hand::Hour => &self.hour,
+            Hand::Minute => &self.minute,
+        }
+    }
+}
+
+/// Returns the eased progress (0.0-1.0) of a color cross-fade.
+///
+/// # Arguments
+/// * `start` - When the fade started
+pub fn fade_progress(start: Instant) -> f64 {
+    let progress = start.elapsed().as_millis() as f64 / COLOR_FADE_DURATION_MS as f64;
+    AnalogClock::ease_in_out(progress.min(1.0))
+}
+
+/// Linearly interpolates between two RGBA colors.
+///
+/// # Arguments
+/// * `from` - Color at `t = 0.0`
+/// * `to` - Color at `t = 1.0`
+/// * `t` - Progress, clamped to 0.0-1.0
+pub fn lerp_color(
+    from: (f64, f64, f64, f64),
+    to: (f64, f64, f64, f64),
+    t: f64,
+) -> (f64, f64, f64, f64) {
+    let t = t.clamp(0.0, 1.0);
+    (
+        from.0 + (to.0 - from.0) * t,
+        from.1 + (to.1 - from.1) * t,
+        from.2 + (to.2 - from.2) * t,
+        from.3 + (to.3 - from.3) * t,
+    )
+}
+
+/// Returns where a face's tick marks go.
+///
+/// # Returns
+/// One `(angle, quarter)` per mark: the angle in degrees clockwise from 12
+/// o'clock, and whether it marks a quarter hour (12, 3, 6 or 9 o'clock)
+fn tick_marks(ticks: TickMarks) -> Vec<(f64, bool)> {
+    let count = match ticks {
+        TickMarks::None => 0,
+        TickMarks::Quarters => 4,
+        TickMarks::Hours => 12,
+    };
+    (0..count)
+        .map(|tick| {
+            let hour = tick * 12 / count;
+            (f64::from(hour) * 30.0, hour % 3 == 0)
+        })
+        .collect()
+}
+
+/// Returns the motion trail of a hand rotating from `start` to `target`:
+/// its angles at the past `trail_length` frames, newest first, with their
+/// opacity.
+///
+/// The angles follow the easing curve, and each copy's opacity falls off
+/// with its age and rises with the hand's speed at that point, so the trail
+/// grows in the middle of a sweep and fades as it settles.
+///
+/// # Arguments
+/// * `(start, target)` - Cumulative angles of the rotation in degrees
+/// * `progress` - How far the rotation is, from 0.0 to 1.0
+/// * `step` - The progress made in one frame
+/// * `effects` - Trail length and intensity
+///
+/// # Returns
+/// `(angle, opacity)` pairs, empty when the hand doesn't move
+fn trail_copies(
+    (start, target): (f64, f64),
+    progress: f64,
+    step: f64,
+    effects: EffectsConfig,
+) -> Vec<(f64, f64)> {
+    let angle_at = |t: f64| start + (target - start) * AnalogClock::ease_in_out(t.clamp(0.0, 1.0));
+    let copies = effects.trail_length;
+    (1..=copies)
+        .filter_map(|age| {
+            let t = progress - f64::from(age) * step;
+            if t <= 0.0 {
+                return None;
+            }
+            let angle = angle_at(t);
+            let speed = (angle_at(t + step) - angle).abs();
+            let fade = 1.0 - f64::from(age) / f64::from(copies + 1);
+            let alpha = effects.trail_intensity * fade * (speed / TRAIL_FULL_SPEED).min(1.0);
+            (alpha > 0.0).then_some((angle, alpha))
+        })
+        .collect()
 }
 
 mod imp {
@@ -91,13 +295,19 @@ mod imp {
         pub last_minute_angle: RefCell<Option<f64>>,
         pub size: RefCell<i32>,
         pub stroke_width: RefCell<f64>,
+        pub hands: RefCell<HandsConfig>,
+        pub effects: RefCell<EffectsConfig>,
         pub colors: RefCell<ClockColors>,
+        pub faces: RefCell<ClockFaces>,
         pub is_active: RefCell<bool>,
         pub target_is_active: RefCell<bool>,
         pub animation_duration_ms: RefCell<u64>,
         pub animation_start_time: RefCell<Option<std::time::Instant>>,
         pub start_cumulative_hour: RefCell<f64>,
         pub start_cumulative_minute: RefCell<f64>,
+        /// Colors being faded out, while a color cross-fade runs
+        pub previous_colors: RefCell<Option<ClockColors>>,
+        pub color_fade_start: RefCell<Option<Instant>>,
     }
 
     impl Default for AnalogClock {
@@ -113,18 +323,30 @@ mod imp {
                 last_minute_angle: RefCell::new(None),
                 size: RefCell::new(40),
                 stroke_width: RefCell::new(2.0),
+                hands: RefCell::new(HandsConfig::default()),
                 colors: RefCell::new(ClockColors {
-                    active_color: (1.0, 0.42, 0.42, 1.0),    // #ff6b6b
-                    inactive_color: (1.0, 0.42, 0.42, 0.15), // #ff6b6b26
-                    bg_color: (1.0, 1.0, 1.0, 0.03),         // #ffffff08
-                    border_color: (1.0, 1.0, 1.0, 0.1),      // #ffffff1a
+                    hour: HandRgba {
+                        active: (1.0, 0.42, 0.42, 1.0),    // #ff6b6b
+                        inactive: (1.0, 0.42, 0.42, 0.15), // #ff6b6b26
+                    },
+                    minute: HandRgba {
+                        active: (1.0, 0.42, 0.42, 1.0),
+                        inactive: (1.0, 0.42, 0.42, 0.15),
+                    },
+                    bg_color: (1.0, 1.0, 1.0, 0.03),    // #ffffff08
+                    border_color: (1.0, 1.0, 1.0, 0.1), // #ffffff1a
+                    shadow_color: (0.0, 0.0, 0.0, 0.5), // #00000080
                 }),
+                effects: RefCell::new(EffectsConfig::default()),
+                faces: RefCell::new(ClockFaces::from_config(&FaceConfig::default())),
                 is_active: RefCell::new(true),
                 target_is_active: RefCell::new(true),
                 animation_duration_ms: RefCell::new(300),
                 animation_start_time: RefCell::new(None),
                 start_cumulative_hour: RefCell::new(0.0),
                 start_cumulative_minute: RefCell::new(0.0),
+                previous_colors: RefCell::new(None),
+                color_fade_start: RefCell::new(None),
             }
         }
     }
@@ -153,25 +375,29 @@ impl AnalogClock {
     /// with two hands. The clock automatically starts its 60 FPS animation loop.
     ///
     /// # Arguments
-    /// * `size` - Clock diameter in pixels
-    /// * `stroke_width` - Width of clock hands in pixels
-    /// * `colors` - Color scheme for active/inactive states and clock face
+    /// * `style` - Size, hands, colors, faces and effects of the clock
     /// * `animation_duration_ms` - Duration of hand rotation animations in milliseconds
     ///
     /// # Returns
     /// A new `AnalogClock` widget ready to be added to a GTK container
-    pub fn new(
-        size: i32,
-        stroke_width: f64,
-        colors: ClockColors,
-        animation_duration_ms: u64,
-    ) -> Self {
+    pub fn new(style: ClockStyle, animation_duration_ms: u64) -> Self {
+        let ClockStyle {
+            size,
+            stroke_width,
+            hands,
+            colors,
+            faces,
+            effects,
+        } = style;
         let obj: Self = glib::Object::new();
 
         let imp = obj.imp();
         *imp.size.borrow_mut() = size;
         *imp.stroke_width.borrow_mut() = stroke_width;
+        *imp.hands.borrow_mut() = hands;
         *imp.colors.borrow_mut() = colors;
+        *imp.faces.borrow_mut() = faces;
+        *imp.effects.borrow_mut() = effects;
         *imp.animation_duration_ms.borrow_mut() = animation_duration_ms;
 
         obj.set_content_width(size);
@@ -268,6 +494,69 @@ impl AnalogClock {
         self.queue_draw();
     }
 
+    /// Changes the clock's colors, cross-fading from the current ones.
+    ///
+    /// If a fade is already running, the new fade starts from the colors
+    /// currently on screen. Setting the same colors again does nothing.
+    ///
+    /// # Arguments
+    /// * `colors` - The new color scheme
+    pub fn set_colors(&self, colors: ClockColors) {
+        let imp = self.imp();
+        if *imp.colors.borrow() == colors {
+            return;
+        }
+
+        let current = self.current_colors();
+        *imp.previous_colors.borrow_mut() = Some(current);
+        *imp.color_fade_start.borrow_mut() = Some(Instant::now());
+        *imp.colors.borrow_mut() = colors;
+        self.queue_draw();
+    }
+
+    /// Changes the face styles, redrawing at once.
+    pub fn set_faces(&self, faces: ClockFaces) {
+        *self.imp().faces.borrow_mut() = faces;
+        self.queue_draw();
+    }
+
+    /// Changes the width of the clock hands.
+    pub fn set_stroke_width(&self, stroke_width: f64) {
+        *self.imp().stroke_width.borrow_mut() = stroke_width;
+        self.queue_draw();
+    }
+
+    /// Changes the shapes of the hands, redrawing at once.
+    pub fn set_hands(&self, hands: HandsConfig) {
+        *self.imp().hands.borrow_mut() = hands;
+        self.queue_draw();
+    }
+
+    /// Changes the glow and shadow of the hands, redrawing at once.
+    pub fn set_effects(&self, effects: EffectsConfig) {
+        *self.imp().effects.borrow_mut() = effects;
+        self.queue_draw();
+    }
+
+    /// Changes the duration of future hand rotations.
+    pub fn set_animation_duration(&self, animation_duration_ms: u64) {
+        *self.imp().animation_duration_ms.borrow_mut() = animation_duration_ms;
+    }
+
+    /// Returns the colors to draw with, blended while a cross-fade runs.
+    fn current_colors(&self) -> ClockColors {
+        let imp = self.imp();
+        let colors = imp.colors.borrow();
+
+        match (
+            &*imp.previous_colors.borrow(),
+            *imp.color_fade_start.borrow(),
+        ) {
+            (Some(previous), Some(start)) => previous.lerp(&colors, fade_progress(start)),
+            _ => colors.clone(),
+        }
+    }
+
     /// Calculates the cumulative target angle to ensure clockwise rotation.
     ///
     /// This is the key function that prevents backwards rotation. It compares
@@ -360,9 +649,20 @@ impl AnalogClock {
     ///
     /// Called every 16ms by the animation loop. Interpolates between start
     /// and target angles using an ease-in-out curve. When animation completes,
-    /// snaps to exact target values and clears the animation timer.
+    /// snaps to exact target values and clears the animation timer. Also
+    /// redraws while a color cross-fade runs.
     fn update_animation(&self) {
         let imp = self.imp();
+
+        let fade_start = *imp.color_fade_start.borrow();
+        if let Some(start) = fade_start {
+            if start.elapsed() >= Duration::from_millis(COLOR_FADE_DURATION_MS) {
+                *imp.previous_colors.borrow_mut() = None;
+                *imp.color_fade_start.borrow_mut() = None;
+            }
+            self.queue_draw();
+        }
+
         let animation_start = *imp.animation_start_time.borrow();
 
         if let Some(start_time) = animation_start {
@@ -418,17 +718,64 @@ impl AnalogClock {
         }
     }
 
+    /// Returns the eased progress (0.0-1.0) of a change between the active
+    /// and inactive states, or `None` if the clock isn't changing state.
+    fn state_transition(&self) -> Option<f64> {
+        let imp = self.imp();
+        let start_time = (*imp.animation_start_time.borrow())?;
+        if *imp.is_active.borrow() == *imp.target_is_active.borrow() {
+            return None;
+        }
+
+        let elapsed = start_time.elapsed().as_millis() as f64;
+        let duration = *imp.animation_duration_ms.borrow() as f64;
+        Some(Self::ease_in_out((elapsed / duration).min(1.0)))
+    }
+
+    /// Returns the motion trail of a hand during a rotation (see
+    /// `trail_copies`).
+    ///
+    /// # Arguments
+    /// * `hand` - Which hand
+    /// * `effects` - Trail length and intensity
+    ///
+    /// # Returns
+    /// `(angle, opacity)` pairs, empty while the hand is still
+    fn trail(&self, hand: Hand, effects: EffectsConfig) -> Vec<(f64, f64)> {
+        let imp = self.imp();
+        let Some(start_time) = *imp.animation_start_time.borrow() else {
+            return Vec::new();
+        };
+        let duration = *imp.animation_duration_ms.borrow() as f64;
+        if duration <= 0.0 {
+            return Vec::new();
+        }
+
+        let (start, target) = match hand {
+            Hand::Hour => (
+                *imp.start_cumulative_hour.borrow(),
+                *imp.target_cumulative_hour.borrow(),
+            ),
+            Hand::Minute => (
+                *imp.start_cumulative_minute.borrow(),
+                *imp.target_cumulative_minute.borrow(),
+            ),
+        };
+        let progress = (start_time.elapsed().as_millis() as f64 / duration).min(1.0);
+        let step = FRAME_DURATION_MS as f64 / duration;
+        trail_copies((start, target), progress, step, effects)
+    }
+
     /// Renders the clock to the Cairo context.
     ///
     /// Draws all elements of the clock in order:
-    /// 1. Background circle (filled)
-    /// 2. Border circle (stroked)
-    /// 3. Hour hand
-    /// 4. Minute hand
-    /// 5. Center dot
+    /// 1. Face: background, bevel, border and tick marks (see `draw_face`)
+    /// 2. Hour hand
+    /// 3. Minute hand
+    /// 4. Center dot
     ///
-    /// Colors are interpolated during active/inactive transitions for smooth
-    /// visual effects.
+    /// Colors and faces are interpolated during active/inactive transitions
+    /// for smooth visual effects.
     ///
     /// # Arguments
     /// * `cr` - Cairo rendering context
@@ -439,128 +786,403 @@ impl AnalogClock {
 
         let size = *imp.size.borrow();
         let stroke_width = *imp.stroke_width.borrow();
-        let colors = imp.colors.borrow();
+        let colors = self.current_colors();
+        let faces = *imp.faces.borrow();
         let is_active = *imp.is_active.borrow();
+        let target_is_active = *imp.target_is_active.borrow();
+        let transition = self.state_transition();
 
         let center_x = width as f64 / 2.0;
         let center_y = height as f64 / 2.0;
         let radius = (size as f64 / 2.0) - CLOCK_RADIUS_PADDING;
 
-        // Draw clock background
-        let bg = colors.bg_color;
-        cr.set_source_rgba(bg.0, bg.1, bg.2, bg.3);
-        cr.arc(center_x, center_y, radius, 0.0, 2.0 * PI);
-        cr.fill().ok();
+        // Cross-fade the faces while changing between differing ones
+        let face = |active: bool| if active { faces.active } else { faces.inactive };
+        match transition {
+            Some(progress) if face(is_active) != face(target_is_active) => {
+                for (active, alpha) in [(is_active, 1.0 - progress), (target_is_active, progress)] {
+                    cr.push_group();
+                    Self::draw_face(cr, &face(active), &colors, center_x, center_y, radius);
+                    cr.pop_group_to_source().ok();
+                    cr.paint_with_alpha(alpha).ok();
+                }
+            }
+            _ => Self::draw_face(cr, &face(is_active), &colors, center_x, center_y, radius),
+        }
 
-        // Draw clock border
-        let border = colors.border_color;
-        cr.set_source_rgba(border.0, border.1, border.2, border.3);
-        cr.set_line_width(CLOCK_BORDER_WIDTH);
-        cr.arc(center_x, center_y, radius, 0.0, 2.0 * PI);
-        cr.stroke().ok();
+        // Interpolate hand colors during animation
+        let hand_color = |hand: Hand| {
+            let colors = colors.hand(hand);
+            match transition {
+                Some(progress) => lerp_color(
+                    colors.state(is_active),
+                    colors.state(target_is_active),
+                    progress,
+                ),
+                None => colors.state(is_active),
+            }
+        };
 
-        // Interpolate color during animation
-        let hand_color = if let Some(start_time) = *imp.animation_start_time.borrow() {
-            let elapsed = start_time.elapsed().as_millis() as f64;
-            let duration = *imp.animation_duration_ms.borrow() as f64;
-            let target_is_active = *imp.target_is_active.borrow();
+        // Draw the hands, using cumulative angles for smooth rotation
+        let hands = *imp.hands.borrow();
+        let effects = *imp.effects.borrow();
+        let full_length = radius - HAND_LENGTH_REDUCTION;
+        let drawn = [
+            (Hand::Hour, *imp.cumulative_hour_angle.borrow()),
+            (Hand::Minute, *imp.cumulative_minute_angle.borrow()),
+        ]
+        .map(|(hand, angle)| {
+            let style = hands.get(hand);
+            let width = if style.width > 0.0 {
+                style.width
+            } else {
+                stroke_width
+            };
+            (hand, *style, width, hand_color(hand), angle)
+        });
+
+        // Shadows go under both hands, then glows, then the hands themselves
+        if effects.shadow {
+            cr.save().ok();
+            cr.translate(effects.shadow_offset_x, effects.shadow_offset_y);
+            for (_, style, width, _, angle) in &drawn {
+                Self::draw_soft_hand(
+                    cr,
+                    style,
+                    *width,
+                    colors.shadow_color,
+                    effects.shadow_blur,
+                    (center_x, center_y, *angle, full_length),
+                );
+            }
+            cr.restore().ok();
+        }
 
-            // If we're transitioning between states, interpolate colors
-            if is_active != target_is_active {
-                // Calculate progress (clamped to 1.0)
-                let progress = (elapsed / duration).min(1.0);
-                let eased_progress = Self::ease_in_out(progress);
+        let activeness = |active: bool| if active { 1.0 } else { 0.0 };
+        let glow = effects.glow_intensity
+            * match transition {
+                Some(progress) => {
+                    let from = activeness(is_active);
+                    from + (activeness(target_is_active) - from) * progress
+                }
+                None => activeness(is_active),
+            };
+        if effects.glow_radius > 0.0 && glow > 0.0 {
+            for (_, style, width, color, angle) in &drawn {
+                Self::draw_soft_hand(
+                    cr,
+                    style,
+                    *width,
+                    (color.0, color.1, color.2, color.3 * glow),
+                    effects.glow_radius,
+                    (center_x, center_y, *angle, full_length),
+                );
+            }
+        }
 
-                // Interpolate between inactive and active colors
-                let (from_color, to_color) = if target_is_active {
-                    (colors.inactive_color, colors.active_color)
-                } else {
-                    (colors.active_color, colors.inactive_color)
-                };
-
-                (
-                    from_color.0 + (to_color.0 - from_color.0) * eased_progress,
-                    from_color.1 + (to_color.1 - from_color.1) * eased_progress,
-                    from_color.2 + (to_color.2 - from_color.2) * eased_progress,
-                    from_color.3 + (to_color.3 - from_color.3) * eased_progress,
-                )
-            } else {
-                // Same state, use appropriate color
-                if is_active {
-                    colors.active_color
-                } else {
-                    colors.inactive_color
+        if effects.trail_length > 0 {
+            for (hand, style, width, color, _) in &drawn {
+                for (angle, alpha) in self.trail(*hand, effects) {
+                    cr.set_source_rgba(color.0, color.1, color.2, color.3 * alpha);
+                    Self::draw_hand(cr, style, *width, center_x, center_y, angle, full_length);
                 }
             }
-        } else {
-            // Not animating, use current state
-            if is_active {
-                colors.active_color
+        }
+
+        for (_, style, width, color, angle) in &drawn {
+            cr.set_source_rgba(color.0, color.1, color.2, color.3);
+            Self::draw_hand(cr, style, *width, center_x, center_y, *angle, full_length);
+        }
+
+        // Draw center dot with animated opacity
+        let state_opacity = |active: bool| {
+            if active {
+                CENTER_DOT_OPACITY_ACTIVE
             } else {
-                colors.inactive_color
+                CENTER_DOT_OPACITY_INACTIVE
             }
         };
+        let center_opacity = match transition {
+            Some(progress) => {
+                let from_opacity = state_opacity(is_active);
+                from_opacity + (state_opacity(target_is_active) - from_opacity) * progress
+            }
+            None => state_opacity(is_active),
+        };
 
-        cr.set_source_rgba(hand_color.0, hand_color.1, hand_color.2, hand_color.3);
-        cr.set_line_width(stroke_width);
-        cr.set_line_cap(cairo::LineCap::Round);
-
-        // Draw hour hand (using cumulative angle for smooth rotation)
-        let cumulative_hour = *imp.cumulative_hour_angle.borrow();
-        let hour_rad = (cumulative_hour + ANGLE_OFFSET_DEGREES) * PI / 180.0;
-        let hour_length = radius - HAND_LENGTH_REDUCTION;
-
-        cr.move_to(center_x, center_y);
-        cr.line_to(
-            center_x + hour_length * hour_rad.cos(),
-            center_y + hour_length * hour_rad.sin(),
-        );
-        cr.stroke().ok();
+        let dot_color = hand_color(Hand::Hour);
+        cr.set_source_rgba(dot_color.0, dot_color.1, dot_color.2, center_opacity);
+        cr.arc(center_x, center_y, stroke_width, 0.0, 2.0 * PI);
+        cr.fill().ok();
+    }
 
-        // Draw minute hand (using cumulative angle for smooth rotation)
-        let cumulative_minute = *imp.cumulative_minute_angle.borrow();
-        let minute_rad = (cumulative_minute + ANGLE_OFFSET_DEGREES) * PI / 180.0;
-        let minute_length = radius - HAND_LENGTH_REDUCTION;
+    /// Draws a hand widened by up to `spread` pixels with a soft edge, for
+    /// glows and shadows.
+    ///
+    /// The hand is stroked `EFFECT_PASSES` times, each wider and sharing
+    /// `color`'s opacity, so the overlap is densest next to the hand and
+    /// fades out towards the edge. Without a spread it is drawn once.
+    ///
+    /// # Arguments
+    /// * `cr` - Cairo rendering context
+    /// * `style` - Shape of the hand
+    /// * `width` - Width of the hand itself in pixels
+    /// * `color` - Color of the effect
+    /// * `spread` - How far the effect reaches past the hand, in pixels
+    /// * `placement` - Center x and y, angle and full length (see `draw_hand`)
+    fn draw_soft_hand(
+        cr: &Context,
+        style: &HandStyle,
+        width: f64,
+        color: (f64, f64, f64, f64),
+        spread: f64,
+        (center_x, center_y, angle, full_length): (f64, f64, f64, f64),
+    ) {
+        let passes = if spread > 0.0 { EFFECT_PASSES } else { 1 };
+        cr.set_source_rgba(color.0, color.1, color.2, color.3 / passes as f64);
+        for pass in 1..=passes {
+            let grown = width + 2.0 * spread * pass as f64 / passes as f64;
+            Self::draw_hand(cr, style, grown, center_x, center_y, angle, full_length);
+        }
+    }
 
-        cr.move_to(center_x, center_y);
-        cr.line_to(
-            center_x + minute_length * minute_rad.cos(),
-            center_y + minute_length * minute_rad.sin(),
-        );
-        cr.stroke().ok();
+    /// Draws one hand in the current source color.
+    ///
+    /// An untapered hand is a stroked line. A tapered one is filled, its
+    /// base `width` wide and its tip narrower by `style.taper`, with the
+    /// cap shaping both ends. A counterweight is a line behind the center
+    /// ending in a disc.
+    ///
+    /// # Arguments
+    /// * `cr` - Cairo rendering context
+    /// * `style` - Shape of the hand
+    /// * `width` - Width of the hand in pixels
+    /// * `center_x`, `center_y` - Center of the clock
+    /// * `angle` - Direction of the hand in degrees (0 = 12 o'clock)
+    /// * `full_length` - Length of a hand with `length = 1.0`
+    fn draw_hand(
+        cr: &Context,
+        style: &HandStyle,
+        width: f64,
+        center_x: f64,
+        center_y: f64,
+        angle: f64,
+        full_length: f64,
+    ) {
+        let length = full_length * style.length;
+        let tail = length * style.tail;
+
+        // Draw along the positive x axis, rotated into place
+        cr.save().ok();
+        cr.translate(center_x, center_y);
+        cr.rotate((angle + ANGLE_OFFSET_DEGREES) * PI / 180.0);
+        cr.set_line_width(width);
+        cr.set_line_cap(match style.cap {
+            HandCap::Round => cairo::LineCap::Round,
+            HandCap::Butt => cairo::LineCap::Butt,
+            HandCap::Square => cairo::LineCap::Square,
+        });
+
+        if tail > 0.0 {
+            cr.move_to(0.0, 0.0);
+            cr.line_to(-tail, 0.0);
+            cr.stroke().ok();
+            cr.arc(-tail, 0.0, width * COUNTERWEIGHT_RADIUS, 0.0, 2.0 * PI);
+            cr.fill().ok();
+        }
 
-        // Draw center dot with animated opacity
-        let center_opacity = if let Some(start_time) = *imp.animation_start_time.borrow() {
-            let elapsed = start_time.elapsed().as_millis() as f64;
-            let duration = *imp.animation_duration_ms.borrow() as f64;
-            let target_is_active = *imp.target_is_active.borrow();
+        if style.taper > 0.0 {
+            let base = width / 2.0;
+            let tip = base * (1.0 - style.taper);
+            match style.cap {
+                HandCap::Round => {
+                    cr.move_to(0.0, -base);
+                    cr.line_to(length, -tip);
+                    cr.arc(length, 0.0, tip, -PI / 2.0, PI / 2.0);
+                    cr.line_to(0.0, base);
+                    cr.arc(0.0, 0.0, base, PI / 2.0, 3.0 * PI / 2.0);
+                }
+                HandCap::Butt => {
+                    cr.move_to(0.0, -base);
+                    cr.line_to(length, -tip);
+                    cr.line_to(length, tip);
+                    cr.line_to(0.0, base);
+                }
+                HandCap::Square => {
+                    cr.move_to(-base, -base);
+                    cr.line_to(length + tip, -tip);
+                    cr.line_to(length + tip, tip);
+                    cr.line_to(-base, base);
+                }
+            }
+            cr.close_path();
+            cr.fill().ok();
+        } else {
+            cr.move_to(0.0, 0.0);
+            cr.line_to(length, 0.0);
+            cr.stroke().ok();
+        }
 
-            // If we're transitioning between states, interpolate center dot opacity
-            if is_active != target_is_active {
-                let progress = (elapsed / duration).min(1.0);
-                let eased_progress = Self::ease_in_out(progress);
+        cr.restore().ok();
+    }
 
-                // Interpolate between inactive and active opacity
-                let (from_opacity, to_opacity) = if target_is_active {
-                    (CENTER_DOT_OPACITY_INACTIVE, CENTER_DOT_OPACITY_ACTIVE)
-                } else {
-                    (CENTER_DOT_OPACITY_ACTIVE, CENTER_DOT_OPACITY_INACTIVE)
-                };
+    /// Draws a clock face: its background, bevel shading, border and tick
+    /// marks, in the clock's background and border colors.
+    ///
+    /// # Arguments
+    /// * `cr` - Cairo rendering context
+    /// * `face` - How to draw the face
+    /// * `colors` - Colors to draw with
+    /// * `center_x`, `center_y` - Center of the clock
+    /// * `radius` - Half the face's width
+    fn draw_face(
+        cr: &Context,
+        face: &Face,
+        colors: &ClockColors,
+        center_x: f64,
+        center_y: f64,
+        radius: f64,
+    ) {
+        let (left, top) = (center_x - radius, center_y - radius);
+        match face.style {
+            FaceStyle::None => return,
+            FaceStyle::Circle => cr.arc(center_x, center_y, radius, 0.0, 2.0 * PI),
+            FaceStyle::Square => cr.rectangle(left, top, 2.0 * radius, 2.0 * radius),
+            FaceStyle::RoundedSquare => {
+                let corner = radius * ROUNDED_SQUARE_CORNER;
+                let (right, bottom) = (center_x + radius, center_y + radius);
+                cr.new_sub_path();
+                cr.arc(right - corner, top + corner, corner, -PI / 2.0, 0.0);
+                cr.arc(right - corner, bottom - corner, corner, 0.0, PI / 2.0);
+                cr.arc(left + corner, bottom - corner, corner, PI / 2.0, PI);
+                cr.arc(left + corner, top + corner, corner, PI, 1.5 * PI);
+                cr.close_path();
+            }
+        }
 
-                from_opacity + (to_opacity - from_opacity) * eased_progress
-            } else if is_active {
-                CENTER_DOT_OPACITY_ACTIVE
-            } else {
-                CENTER_DOT_OPACITY_INACTIVE
+        // Draw clock background
+        let bg = colors.bg_color;
+        cr.set_source_rgba(bg.0, bg.1, bg.2, bg.3);
+        cr.fill_preserve().ok();
+
+        // Shade the face over its background
+        let bottom = center_y + radius;
+        let shading = match face.bevel {
+            Bevel::None => None,
+            Bevel::Inset => {
+                let gradient = cairo::LinearGradient::new(center_x, top, center_x, bottom);
+                gradient.add_color_stop_rgba(0.0, 0.0, 0.0, 0.0, BEVEL_SHADE_ALPHA);
+                gradient.add_color_stop_rgba(0.35, 0.0, 0.0, 0.0, 0.0);
+                Some(gradient)
+            }
+            Bevel::Raised => {
+                let gradient = cairo::LinearGradient::new(left, top, center_x + radius, bottom);
+                gradient.add_color_stop_rgba(0.0, 1.0, 1.0, 1.0, BEVEL_LIGHT_ALPHA);
+                gradient.add_color_stop_rgba(0.5, 1.0, 1.0, 1.0, 0.0);
+                gradient.add_color_stop_rgba(0.5, 0.0, 0.0, 0.0, 0.0);
+                gradient.add_color_stop_rgba(1.0, 0.0, 0.0, 0.0, BEVEL_SHADE_ALPHA);
+                Some(gradient)
             }
-        } else if is_active {
-            CENTER_DOT_OPACITY_ACTIVE
-        } else {
-            CENTER_DOT_OPACITY_INACTIVE
         };
+        if let Some(gradient) = shading
+            && cr.set_source(&gradient).is_ok()
+        {
+            cr.fill_preserve().ok();
+        }
 
-        cr.set_source_rgba(hand_color.0, hand_color.1, hand_color.2, center_opacity);
-        cr.arc(center_x, center_y, stroke_width, 0.0, 2.0 * PI);
-        cr.fill().ok();
+        // Draw clock border
+        let border = colors.border_color;
+        cr.set_source_rgba(border.0, border.1, border.2, border.3);
+        if face.border_width > 0.0 {
+            cr.set_line_width(face.border_width);
+            cr.stroke_preserve().ok();
+        }
+        cr.new_path();
+
+        // Tick marks, longer at the quarter hours
+        let ticks = tick_marks(face.ticks);
+        let outer = radius - TICK_INSET;
+        for &(angle, quarter) in &ticks {
+            let length = if quarter {
+                radius * QUARTER_TICK_LENGTH
+            } else {
+                radius * HOUR_TICK_LENGTH
+            };
+            let angle = (angle + ANGLE_OFFSET_DEGREES) * PI / 180.0;
+            let (sin, cos) = angle.sin_cos();
+            cr.move_to(center_x + outer * cos, center_y + outer * sin);
+            cr.line_to(
+                center_x + (outer - length) * cos,
+                center_y + (outer - length) * sin,
+            );
+        }
+        if !ticks.is_empty() {
+            cr.set_line_width(TICK_WIDTH);
+            cr.set_line_cap(cairo::LineCap::Butt);
+            cr.stroke().ok();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lerp_color() {
+        let black = (0.0, 0.0, 0.0, 1.0);
+        let white = (1.0, 1.0, 1.0, 0.0);
+        assert_eq!(lerp_color(black, white, 0.0), black);
+        assert_eq!(lerp_color(black, white, 1.0), white);
+        assert_eq!(lerp_color(black, white, 0.5), (0.5, 0.5, 0.5, 0.5));
+
+        // Out-of-range progress is clamped to the endpoints
+        assert_eq!(lerp_color(black, white, -1.0), black);
+        assert_eq!(lerp_color(black, white, 2.0), white);
+    }
+
+    #[test]
+    fn test_tick_marks() {
+        assert!(tick_marks(TickMarks::None).is_empty());
+
+        let quarters = tick_marks(TickMarks::Quarters);
+        assert_eq!(
+            quarters,
+            vec![(0.0, true), (90.0, true), (180.0, true), (270.0, true)]
+        );
+
+        let hours = tick_marks(TickMarks::Hours);
+        assert_eq!(hours.len(), 12);
+        assert_eq!(hours[1], (30.0, false));
+        assert_eq!(hours[11], (330.0, false));
+        let quarter_angles: Vec<f64> = hours
+            .iter()
+            .filter(|(_, quarter)| *quarter)
+            .map(|(angle, _)| *angle)
+            .collect();
+        assert_eq!(quarter_angles, vec![0.0, 90.0, 180.0, 270.0]);
+    }
+
+    #[test]
+    fn test_trail_copies() {
+        let effects = EffectsConfig {
+            trail_length: 4,
+            trail_intensity: 0.8,
+            ..EffectsConfig::default()
+        };
+        let step = FRAME_DURATION_MS as f64 / 300.0;
+
+        // Nothing trails a hand at rest, or one that hasn't started moving
+        assert!(trail_copies((90.0, 90.0), 0.5, step, effects).is_empty());
+        assert!(trail_copies((0.0, 360.0), 0.0, step, effects).is_empty());
+        assert!(trail_copies((0.0, 360.0), 0.5, step, EffectsConfig::default()).is_empty());
+
+        // Mid-sweep the hand is at full speed, so only age fades the copies
+        let trail = trail_copies((0.0, 360.0), 0.5, step, effects);
+        assert_eq!(trail.len(), 4);
+        assert!((trail[0].1 - 0.8 * 0.8).abs() < 1e-9);
+        assert!((trail[3].1 - 0.8 * 0.2).abs() < 1e-9);
+        assert!(trail.windows(2).all(|pair| pair[0].0 > pair[1].0));
+        assert!(trail[0].0 < 180.0);
     }
 }
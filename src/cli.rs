@@ -0,0 +1,377 @@
+//! Command-line handling.
+//!
+//! Chronomatrix is a single-instance application. Running `chronomatrix`
+//! while a window is already open forwards the command line to the running
+//! instance (via `HANDLES_COMMAND_LINE`), which translates each option into
+//! the matching `app.*` action. This makes the clock scriptable from cron
+//! jobs and editor hooks:
+//!
+//! ```text
+//! chronomatrix --timer 5m
+//! chronomatrix --message "DEPLOY FREEZE"
+//! chronomatrix --mode clock
+//! ```
+//!
+//! Options that correspond to config fields (`--fullscreen`, `--format`,
+//! `--timezone`, `--theme`) are recorded in the shared `ConfigSource` and take
+//! precedence over the config file and its includes, including across hot
+//! reloads. `--config` selects the file to read from then on. `--mode` is an
+//! override only at startup; forwarded to a running instance it switches the
+//! mode once and is not kept as an override. `--version`,
+//! `--print-default-config` and `--validate-config` are answered by the
+//! invoking process and exit.
+
+use gtk4::prelude::*;
+use gtk4::{Application, gio, glib};
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+
+use crate::config::{Config, ConfigSource, DisplayMode, TimeFormat, parse_timezone};
+use crate::mode::parse_duration;
+
+/// Registers the options understood by `handle_local_options` and
+/// `handle_command_line`.
+pub fn register_options(app: &Application) {
+    app.add_main_option(
+        "config",
+        glib::Char::from(b'c'),
+        glib::OptionFlags::NONE,
+        glib::OptionArg::Filename,
+        "Read configuration from FILE instead of the default location",
+        Some("FILE"),
+    );
+    app.add_main_option(
+        "mode",
+        glib::Char::from(b'm'),
+        glib::OptionFlags::NONE,
+        glib::OptionArg::String,
+        "Switch display mode (clock, timer, stopwatch or ambient)",
+        Some("MODE"),
+    );
+    app.add_main_option(
+        "fullscreen",
+        glib::Char::from(b'f'),
+        glib::OptionFlags::NONE,
+        glib::OptionArg::None,
+        "Show the clock fullscreen",
+        None,
+    );
+    app.add_main_option(
+        "format",
+        glib::Char::from(0),
+        glib::OptionFlags::NONE,
+        glib::OptionArg::String,
+        "Hour format of the clock (24h or 12h)",
+        Some("FORMAT"),
+    );
+    app.add_main_option(
+        "timezone",
+        glib::Char::from(0),
+        glib::OptionFlags::NONE,
+        glib::OptionArg::String,
+        "Show the time in a timezone (e.g. Europe/Berlin or UTC)",
+        Some("ZONE"),
+    );
+    app.add_main_option(
+        "theme",
+        glib::Char::from(0),
+        glib::OptionFlags::NONE,
+        glib::OptionArg::String,
+        "Use a built-in or user theme (e.g. nord)",
+        Some("NAME"),
+    );
+    app.add_main_option(
+        "timer",
+        glib::Char::from(b't'),
+        glib::OptionFlags::NONE,
+        glib::OptionArg::String,
+        "Start a countdown timer (e.g. 90s, 5m, 1h30m or 1:30)",
+        Some("DURATION"),
+    );
+    app.add_main_option(
+        "message",
+        glib::Char::from(b'M'),
+        glib::OptionFlags::NONE,
+        glib::OptionArg::String,
+        "Scroll a message across the display",
+        Some("TEXT"),
+    );
+    app.add_main_option(
+        "toggle",
+        glib::Char::from(0),
+        glib::OptionFlags::NONE,
+        glib::OptionArg::None,
+        "Start or pause the timer or stopwatch",
+        None,
+    );
+    app.add_main_option(
+        "reset",
+        glib::Char::from(0),
+        glib::OptionFlags::NONE,
+        glib::OptionArg::None,
+        "Reset the timer or stopwatch",
+        None,
+    );
+    app.add_main_option(
+        "reload",
+        glib::Char::from(0),
+        glib::OptionFlags::NONE,
+        glib::OptionArg::None,
+        "Reload the configuration",
+        None,
+    );
+    app.add_main_option(
+        "toggle-fullscreen",
+        glib::Char::from(0),
+        glib::OptionFlags::NONE,
+        glib::OptionArg::None,
+        "Enter or leave fullscreen",
+        None,
+    );
+    app.add_main_option(
+        "quit",
+        glib::Char::from(b'q'),
+        glib::OptionFlags::NONE,
+        glib::OptionArg::None,
+        "Quit the running instance",
+        None,
+    );
+    app.add_main_option(
+        "print-default-config",
+        glib::Char::from(0),
+        glib::OptionFlags::NONE,
+        glib::OptionArg::None,
+        "Print the default configuration and exit",
+        None,
+    );
+    app.add_main_option(
+        "validate-config",
+        glib::Char::from(0),
+        glib::OptionFlags::NONE,
+        glib::OptionArg::None,
+        "Check the configuration for errors and exit",
+        None,
+    );
+    app.add_main_option(
+        "version",
+        glib::Char::from(b'V'),
+        glib::OptionFlags::NONE,
+        glib::OptionArg::None,
+        "Print the version and exit",
+        None,
+    );
+}
+
+/// Records config-related options in a `ConfigSource`.
+///
+/// # Arguments
+/// * `options` - The parsed command-line options
+/// * `source` - Source to update
+/// * `cwd` - Directory a relative `--config` path is resolved against
+/// * `is_running` - Whether a window is already open, in which case `--mode`
+///   is left to the `set-mode` action instead of becoming an override
+///
+/// # Returns
+/// `true` if any option changed the source
+fn apply_config_options(
+    options: &glib::VariantDict,
+    source: &mut ConfigSource,
+    cwd: Option<&Path>,
+    is_running: bool,
+) -> bool {
+    let lookup_str = |name: &str| options.lookup::<String>(name).ok().flatten();
+    let mut changed = false;
+
+    if let Some(path) = options.lookup::<PathBuf>("config").ok().flatten() {
+        source.path = Some(cwd.map_or_else(|| path.clone(), |cwd| cwd.join(&path)));
+        changed = true;
+    }
+    if !is_running && let Some(mode) = lookup_str("mode") {
+        source.set(
+            "clock",
+            "mode",
+            toml::Value::String(mode.trim().to_ascii_lowercase()),
+        );
+        changed = true;
+    }
+    if let Some(format) = lookup_str("format") {
+        source.set(
+            "clock",
+            "format",
+            toml::Value::String(format.to_ascii_lowercase()),
+        );
+        changed = true;
+    }
+    if let Some(timezone) = lookup_str("timezone") {
+        source.set("clock", "timezone", toml::Value::String(timezone));
+        changed = true;
+    }
+    if let Some(theme) = lookup_str("theme") {
+        source.set("theme", "name", toml::Value::String(theme));
+        changed = true;
+    }
+    if options.contains("fullscreen") {
+        source.set(
+            "window",
+            "mode",
+            toml::Value::String("fullscreen".to_string()),
+        );
+        changed = true;
+    }
+
+    changed
+}
+
+/// Validates options in the invoking process, before they are forwarded.
+///
+/// Runs locally even when another instance is already running, so mistakes
+/// are reported on the terminal that made them. Also answers the options that
+/// only print something (`--version`, `--print-default-config`,
+/// `--validate-config`).
+///
+/// # Returns
+/// `-1` to continue with normal handling, or an exit status to stop with
+pub fn handle_local_options(_app: &Application, options: &glib::VariantDict) -> i32 {
+    let lookup_str = |name: &str| options.lookup::<String>(name).ok().flatten();
+
+    if options.contains("version") {
+        println!("chronomatrix {}", env!("CARGO_PKG_VERSION"));
+        return 0;
+    }
+
+    if let Some(Err(e)) = lookup_str("mode").as_deref().map(str::parse::<DisplayMode>) {
+        eprintln!("{}", e);
+        return 1;
+    }
+
+    if let Some(Err(e)) = lookup_str("timer").as_deref().map(parse_duration) {
+        eprintln!("{}", e);
+        return 1;
+    }
+
+    if let Some(Err(e)) = lookup_str("format")
+        .as_deref()
+        .map(str::parse::<TimeFormat>)
+    {
+        eprintln!("{}", e);
+        return 1;
+    }
+
+    if let Some(Err(e)) = lookup_str("timezone").as_deref().map(parse_timezone) {
+        eprintln!("{}", e);
+        return 1;
+    }
+
+    if options.contains("print-default-config") {
+        return match Config::default_toml() {
+            Ok(text) => {
+                print!("{}", text);
+                0
+            }
+            Err(e) => {
+                eprintln!("Could not serialize the default config: {}", e);
+                1
+            }
+        };
+    }
+
+    if options.contains("validate-config") {
+        let mut source = ConfigSource::default();
+        apply_config_options(options, &mut source, None, false);
+        let path = source.path();
+        return match Config::load(&path, &source.overrides, &source.system) {
+            Ok(result) => {
+                for diagnostic in &result.diagnostics {
+                    eprintln!("{}", diagnostic);
+                }
+                println!(
+                    "{}: OK ({} file(s))",
+                    path.display(),
+                    result.source_files.len()
+                );
+                0
+            }
+            Err(e) => {
+                eprintln!("{}", e);
+                1
+            }
+        };
+    }
+
+    -1
+}
+
+/// Handles a command line, from this process or forwarded from another.
+///
+/// Config-related options update `source` first; when a window is already
+/// open they trigger a reload so they apply immediately. Other options are
+/// applied in a fixed order through the `app.*` actions. The window is
+/// created on first use; a bare `chronomatrix` with a window already open
+/// raises it.
+///
+/// # Arguments
+/// * `app` - The application
+/// * `cmdline` - The command line, possibly from another process
+/// * `source` - The config source shared with the UI
+///
+/// # Returns
+/// The exit status for the invoking process
+pub fn handle_command_line(
+    app: &Application,
+    cmdline: &gio::ApplicationCommandLine,
+    source: &RefCell<ConfigSource>,
+) -> i32 {
+    let options = cmdline.options_dict();
+    let lookup_str = |name: &str| options.lookup::<String>(name).ok().flatten();
+    let flag = |name: &str| options.contains(name);
+
+    if flag("quit") {
+        app.quit();
+        return 0;
+    }
+
+    let is_running = !app.windows().is_empty();
+    let config_changed = apply_config_options(
+        &options,
+        &mut source.borrow_mut(),
+        cmdline.cwd().as_deref(),
+        is_running,
+    );
+    let mut actions: Vec<(&str, Option<glib::Variant>)> = Vec::new();
+
+    if flag("reload") || (is_running && config_changed) {
+        actions.push(("reload-config", None));
+    }
+    // At startup the mode comes from the config overrides; afterwards it is
+    // switched live, once, without touching them
+    if is_running && let Some(mode) = lookup_str("mode") {
+        actions.push(("set-mode", Some(mode.to_variant())));
+    }
+    if let Some(timer) = lookup_str("timer") {
+        actions.push(("start-timer", Some(timer.to_variant())));
+    }
+    if flag("reset") {
+        actions.push(("reset", None));
+    }
+    if flag("toggle") {
+        actions.push(("toggle-running", None));
+    }
+    if let Some(message) = lookup_str("message") {
+        actions.push(("show-message", Some(message.to_variant())));
+    }
+    if flag("toggle-fullscreen") {
+        actions.push(("toggle-fullscreen", None));
+    }
+
+    // Create the window on first launch; otherwise only raise it when the
+    // invocation carried no commands, so scripted calls don't steal focus
+    if !is_running || actions.is_empty() {
+        app.activate();
+    }
+
+    for (name, param) in actions {
+        app.activate_action(name, param.as_ref());
+    }
+
+    0
+}
@@ -4,21 +4,37 @@
 //! in HH:MM:SS format. It manages:
 //! - 6 `DigitDisplay` widgets (2 for hours, 2 for minutes, 2 for seconds)
 //! - Separator dots (`:`) between digit pairs
-//! - Time updates using the system clock via chrono
+//! - Time updates using the system clock via chrono, in 24- or 12-hour
+//!   format and an optional timezone
+//! - Timer and stopwatch modes (see `mode`)
+//! - Ambient mode, in which every analog clock shows the time on its hands
+//!   and the digits only appear for part of each cycle
+//! - Scrolling messages that temporarily replace the time
+//! - Applying config changes in place (`apply_config`), so only changes to
+//!   the layout require building a new display
+//! - Re-evaluating `[[colors.schedule]]` every second (see `schedule`)
 //!
 //! The layout is: `[HH] : [MM] : [SS]` where each digit is a 6x4 grid
 //! of 24 analog clocks.
 
-use chrono::Local;
+use chrono::Utc;
 use gtk4::prelude::*;
 use gtk4::{Box as GtkBox, DrawingArea, Orientation, Widget, glib};
-use std::cell::RefCell;
+use std::cell::{Cell, RefCell};
 use std::f64::consts::PI;
 use std::rc::Rc;
-
-use crate::analog_clock::ClockColors;
-use crate::config::{Config, parse_hex_color};
+use std::time::{Duration, Instant};
+
+use crate::analog_clock::{
+    COLOR_FADE_DURATION_MS, ClockColors, ClockFaces, ClockStyle, FRAME_DURATION_MS, HandRgba,
+    fade_progress, lerp_color,
+};
+use crate::color::parse_color_or_black;
+use crate::config::{AmbientConfig, ColorConfig, Config, DisplayMode, Hand};
 use crate::digit_display::DigitDisplay;
+use crate::glyph_patterns::{BLANK_COLUMN, ClockColumn, render_text};
+use crate::mode::{ModeState, WallClock, ambient_shows_digits, analog_angles};
+use crate::schedule::colors_at;
 
 /// Width of the separator area containing the colon dots
 const SEPARATOR_WIDTH: i32 = 20;
@@ -38,9 +54,53 @@ const SEPARATOR_DOT_RADIUS: f64 = 4.0;
 /// Gap between digit groups (hours, minutes, seconds)
 const DIGIT_GROUP_GAP: i32 = 20;
 
+/// Message scrolled when a countdown timer runs out
+const TIMER_FINISHED_MESSAGE: &str = "TIME UP";
+
+/// A message scrolling across the display in place of the time.
+struct Marquee {
+    /// Blank lead-in followed by the rendered message
+    columns: Vec<ClockColumn>,
+    /// Index into `columns` shown in the leftmost display column
+    offset: usize,
+    /// When the message gives way to the time again
+    ends_at: Instant,
+    /// Identifies the timer driving this message so superseded timers stop
+    generation: u64,
+}
+
+/// Color of the separator dots, with the color it is fading from.
+struct SeparatorColor {
+    color: (f64, f64, f64, f64),
+    previous: (f64, f64, f64, f64),
+    /// When the current cross-fade started, if one is running
+    fade_start: Option<Instant>,
+}
+
+impl SeparatorColor {
+    /// Returns the color to draw with, blended while a cross-fade runs.
+    fn current(&self) -> (f64, f64, f64, f64) {
+        match self.fade_start {
+            Some(start) => lerp_color(self.previous, self.color, fade_progress(start)),
+            None => self.color,
+        }
+    }
+}
+
 pub struct ClockDisplay {
     container: GtkBox,
-    digits: Vec<DigitDisplay>,
+    digits: Rc<Vec<DigitDisplay>>,
+    separators: Vec<DrawingArea>,
+    separator_color: Rc<RefCell<SeparatorColor>>,
+    /// Configured colors, including their schedule
+    colors: RefCell<ColorConfig>,
+    marquee: Rc<RefCell<Option<Marquee>>>,
+    marquee_generation: Cell<u64>,
+    marquee_duration: Cell<Duration>,
+    marquee_step: Cell<Duration>,
+    mode: Rc<RefCell<ModeState>>,
+    wall_clock: Rc<Cell<WallClock>>,
+    ambient: Rc<Cell<AmbientConfig>>,
 }
 
 impl ClockDisplay {
@@ -54,7 +114,8 @@ impl ClockDisplay {
     /// * `config` - Configuration containing colors, sizes, and animation settings
     ///
     /// # Returns
-    /// A new `ClockDisplay` ready to be added to a GTK container
+    /// A new `ClockDisplay` ready to be added to a GTK container, in the
+    /// configured startup mode
     pub fn new(config: &Config) -> Self {
         let container = GtkBox::new(Orientation::Horizontal, DIGIT_GROUP_GAP);
         container.set_halign(gtk4::Align::Center);
@@ -63,26 +124,31 @@ impl ClockDisplay {
         // Apply display styling
         container.add_css_class("clock-display");
 
-        let clock_colors = ClockColors {
-            active_color: parse_hex_color(&config.colors.clock_hand_color),
-            inactive_color: parse_hex_color(&config.colors.clock_hand_inactive),
-            bg_color: parse_hex_color(&config.colors.clock_bg),
-            border_color: parse_hex_color(&config.colors.clock_border),
-        };
+        let wall_clock = Self::wall_clock(config);
+        let colors = colors_at(&config.colors, wall_clock.time_of_day(Utc::now()));
 
-        let separator_color = Rc::new(RefCell::new(parse_hex_color(
-            &config.colors.separator_color,
-        )));
+        let color = parse_color_or_black(&colors.separator_color);
+        let separator_color = Rc::new(RefCell::new(SeparatorColor {
+            color,
+            previous: color,
+            fade_start: None,
+        }));
 
         let mut digits = Vec::new();
 
         // Create 6 digits (HH:MM:SS)
-        for _ in 0..6 {
+        for index in 0..6 {
+            let style = ClockStyle {
+                size: config.clock.size,
+                stroke_width: config.clock.stroke_width,
+                hands: config.hands,
+                colors: Self::clock_colors(&colors, index),
+                faces: ClockFaces::from_config(&config.face),
+                effects: config.effects,
+            };
             let digit = DigitDisplay::new(
-                config.clock.size,
-                config.clock.stroke_width,
+                style,
                 config.clock.clock_gap,
-                clock_colors.clone(),
                 config.clock.animation_duration_ms,
             );
             digits.push(digit);
@@ -111,7 +177,155 @@ impl ClockDisplay {
         container.append(&sep2);
         container.append(&seconds_box);
 
-        ClockDisplay { container, digits }
+        let mut mode = ModeState::default();
+        mode.set_mode(config.clock.mode);
+
+        ClockDisplay {
+            container,
+            digits: Rc::new(digits),
+            separators: vec![sep1, sep2],
+            separator_color,
+            colors: RefCell::new(config.colors.clone()),
+            marquee: Rc::new(RefCell::new(None)),
+            marquee_generation: Cell::new(0),
+            marquee_duration: Cell::new(Duration::from_secs(config.marquee.duration_secs)),
+            marquee_step: Cell::new(Duration::from_millis(config.marquee.step_ms.max(1))),
+            mode: Rc::new(RefCell::new(mode)),
+            wall_clock: Rc::new(Cell::new(wall_clock)),
+            ambient: Rc::new(Cell::new(config.ambient)),
+        }
+    }
+
+    /// Returns whether a config change needs a new display to take effect.
+    ///
+    /// Sizes and gaps change the widget layout; everything else can be
+    /// applied to the existing display with `apply_config`.
+    pub fn requires_rebuild(old: &Config, new: &Config) -> bool {
+        old.clock.size != new.clock.size
+            || old.clock.clock_gap != new.clock.clock_gap
+            || old.clock.digit_gap != new.clock.digit_gap
+    }
+
+    /// Applies a new config to this display in place.
+    ///
+    /// * Colors cross-fade to their new values.
+    /// * Face styles, hand shapes, effects, animation speed, time format,
+    ///   message and ambient timing change immediately.
+    /// * Hand positions and any running timer are kept.
+    /// * Layout changes are ignored (see `requires_rebuild`).
+    ///
+    /// # Arguments
+    /// * `config` - The new configuration
+    pub fn apply_config(&self, config: &Config) {
+        for digit in self.digits.iter() {
+            digit.set_faces(ClockFaces::from_config(&config.face));
+            digit.set_stroke_width(config.clock.stroke_width);
+            digit.set_hands(config.hands);
+            digit.set_effects(config.effects);
+            digit.set_animation_duration(config.clock.animation_duration_ms);
+        }
+
+        *self.colors.borrow_mut() = config.colors.clone();
+
+        self.marquee_duration
+            .set(Duration::from_secs(config.marquee.duration_secs));
+        self.marquee_step
+            .set(Duration::from_millis(config.marquee.step_ms.max(1)));
+
+        let wall_clock = Self::wall_clock(config);
+        if wall_clock != self.wall_clock.get() || config.ambient != self.ambient.get() {
+            self.wall_clock.set(wall_clock);
+            self.ambient.set(config.ambient);
+            if !self.is_showing_message() {
+                Self::show_time(
+                    &self.digits,
+                    &self.separators,
+                    &self.mode.borrow(),
+                    wall_clock,
+                    config.ambient,
+                    false,
+                );
+            }
+        }
+
+        // After the wall clock, whose timezone the schedule follows
+        self.apply_colors(&self.current_colors());
+    }
+
+    /// Returns the configured colors as the schedule has them right now.
+    pub fn current_colors(&self) -> ColorConfig {
+        let now = self.wall_clock.get().time_of_day(Utc::now());
+        colors_at(&self.colors.borrow(), now)
+    }
+
+    /// Cross-fades the clocks and separators to new colors.
+    fn apply_colors(&self, colors: &ColorConfig) {
+        for (index, digit) in self.digits.iter().enumerate() {
+            digit.set_colors(&Self::clock_colors(colors, index));
+        }
+        self.set_separator_color(parse_color_or_black(&colors.separator_color));
+    }
+
+    /// Builds the clock color scheme of one digit from the `[colors]`
+    /// section, with its own colors for each hand (see
+    /// `ColorConfig::digit_hand_colors`).
+    fn clock_colors(colors: &ColorConfig, index: usize) -> ClockColors {
+        let hand = |hand| {
+            let (active, inactive) = colors.digit_hand_colors(index, hand);
+            HandRgba {
+                active: parse_color_or_black(active),
+                inactive: parse_color_or_black(inactive),
+            }
+        };
+        ClockColors {
+            hour: hand(Hand::Hour),
+            minute: hand(Hand::Minute),
+            bg_color: parse_color_or_black(&colors.clock_bg),
+            border_color: parse_color_or_black(&colors.clock_border),
+            shadow_color: parse_color_or_black(&colors.hand_shadow),
+        }
+    }
+
+    /// Builds the wall clock from a config, falling back to local time.
+    fn wall_clock(config: &Config) -> WallClock {
+        WallClock::new(config.clock.format, &config.clock.timezone).unwrap_or_else(|e| {
+            eprintln!("Warning: {}, using local time", e);
+            WallClock::new(config.clock.format, "").unwrap_or_default()
+        })
+    }
+
+    /// Cross-fades the separator dots to a new color.
+    fn set_separator_color(&self, color: (f64, f64, f64, f64)) {
+        {
+            let mut state = self.separator_color.borrow_mut();
+            if state.color == color {
+                return;
+            }
+            state.previous = state.current();
+            state.color = color;
+            state.fade_start = Some(Instant::now());
+        }
+
+        // Redraw every frame until the fade completes
+        let separators = self.separators.clone();
+        let fade_end = Instant::now() + Duration::from_millis(COLOR_FADE_DURATION_MS);
+        glib::timeout_add_local(Duration::from_millis(FRAME_DURATION_MS), move || {
+            for separator in &separators {
+                separator.queue_draw();
+            }
+            if Instant::now() >= fade_end {
+                glib::ControlFlow::Break
+            } else {
+                glib::ControlFlow::Continue
+            }
+        });
+    }
+
+    /// Shows or hides the separators, keeping their space in the layout.
+    fn show_separators(separators: &[DrawingArea], visible: bool) {
+        for separator in separators {
+            separator.set_opacity(if visible { 1.0 } else { 0.0 });
+        }
     }
 
     /// Creates a separator widget with two dots (`:` character).
@@ -123,7 +337,7 @@ impl ClockDisplay {
     ///
     /// # Returns
     /// A GTK `DrawingArea` widget rendering the separator dots
-    fn create_separator(color: Rc<RefCell<(f64, f64, f64, f64)>>) -> DrawingArea {
+    fn create_separator(color: Rc<RefCell<SeparatorColor>>) -> DrawingArea {
         let separator = DrawingArea::new();
         separator.set_content_width(SEPARATOR_WIDTH);
         separator.set_content_height(SEPARATOR_HEIGHT);
@@ -134,7 +348,7 @@ impl ClockDisplay {
             #[strong]
             color,
             move |_, cr, width, _height| {
-                let (r, g, b, a) = *color.borrow();
+                let (r, g, b, a) = color.borrow().current();
 
                 cr.set_source_rgba(r, g, b, a);
 
@@ -165,21 +379,38 @@ impl ClockDisplay {
         separator
     }
 
-    /// Updates the clock display to show the current system time.
+    /// Updates the clock display to show the current time.
+    ///
+    /// Shows the system time, or the timer/stopwatch reading in those modes,
+    /// and animates each digit to match. Called by a timer every second to
+    /// keep the display synchronized. Does nothing while a message is
+    /// scrolling. A countdown that runs out scrolls `TIMER_FINISHED_MESSAGE`.
+    /// Scheduled colors move on either way.
     ///
-    /// Reads the system time and animates each digit to match. Called by a
-    /// timer every second to keep the display synchronized.
-    pub fn update_time(&self) {
-        let now = Local::now();
-        let time_str = now.format("%H%M%S").to_string();
+    /// # Returns
+    /// `true` if a countdown timer ran out on this update
+    pub fn update_time(&self) -> bool {
+        if !self.colors.borrow().schedule.is_empty() {
+            self.apply_colors(&self.current_colors());
+        }
 
-        for (i, ch) in time_str.chars().enumerate() {
-            if let Some(digit_val) = ch.to_digit(10)
-                && let Some(digit) = self.digits.get(i)
-            {
-                digit.set_digit(digit_val as u8);
-            }
+        if self.mode.borrow_mut().check_finished(Instant::now()) {
+            self.show_message(TIMER_FINISHED_MESSAGE);
+            return true;
         }
+
+        if !self.is_showing_message() {
+            Self::show_time(
+                &self.digits,
+                &self.separators,
+                &self.mode.borrow(),
+                self.wall_clock.get(),
+                self.ambient.get(),
+                false,
+            );
+        }
+
+        false
     }
 
     /// Updates the clock display immediately without animation.
@@ -187,14 +418,225 @@ impl ClockDisplay {
     /// Similar to `update_time()` but uses immediate updates instead of animations.
     /// Used after config reload to avoid animating from the old to new display.
     pub fn update_time_immediate(&self) {
-        let now = Local::now();
-        let time_str = now.format("%H%M%S").to_string();
+        if self.is_showing_message() {
+            return;
+        }
+
+        Self::show_time(
+            &self.digits,
+            &self.separators,
+            &self.mode.borrow(),
+            self.wall_clock.get(),
+            self.ambient.get(),
+            true,
+        );
+    }
+
+    /// Sets every digit to the current reading for the active mode.
+    ///
+    /// In ambient mode outside its digit phase, every clock's hands show the
+    /// system time instead, and the separators are hidden so they don't split
+    /// the analog faces into groups.
+    ///
+    /// # Arguments
+    /// * `digits` - The six digit displays, in HHMMSS order
+    /// * `separators` - The `:` separators between the digit pairs
+    /// * `mode` - Selects between the system time and the timer/stopwatch
+    /// * `wall_clock` - Format and timezone of the system time
+    /// * `ambient` - When ambient mode shows digits
+    /// * `immediate` - Whether to skip the hand animation
+    fn show_time(
+        digits: &[DigitDisplay],
+        separators: &[DrawingArea],
+        mode: &ModeState,
+        wall_clock: WallClock,
+        ambient: AmbientConfig,
+        immediate: bool,
+    ) {
+        let time = wall_clock.time_of_day(Utc::now());
+        let analog = mode.mode() == DisplayMode::Ambient && !ambient_shows_digits(time, &ambient);
+        Self::show_separators(separators, !analog);
+
+        if analog {
+            let (hour, minute) = analog_angles(time);
+            for digit in digits {
+                if immediate {
+                    digit.set_angles_immediate(hour, minute);
+                } else {
+                    digit.set_angles(hour, minute);
+                }
+            }
+            return;
+        }
+
+        let time_str = Self::current_digits(mode, wall_clock);
 
         for (i, ch) in time_str.chars().enumerate() {
             if let Some(digit_val) = ch.to_digit(10)
-                && let Some(digit) = self.digits.get(i)
+                && let Some(digit) = digits.get(i)
             {
-                digit.set_digit_immediate(digit_val as u8);
+                if immediate {
+                    digit.set_digit_immediate(digit_val as u8);
+                } else {
+                    digit.set_digit(digit_val as u8);
+                }
+            }
+        }
+    }
+
+    /// Returns the six HHMMSS digits for the active mode right now.
+    fn current_digits(mode: &ModeState, wall_clock: WallClock) -> String {
+        mode.digits(Instant::now())
+            .unwrap_or_else(|| wall_clock.digits(Utc::now()))
+    }
+
+    /// Returns the current reading formatted as `HH:MM:SS`.
+    ///
+    /// This is the time, timer or stopwatch value for the active mode, even
+    /// while a message is scrolling.
+    pub fn reading(&self) -> String {
+        let digits = Self::current_digits(&self.mode.borrow(), self.wall_clock.get());
+        format!("{}:{}:{}", &digits[0..2], &digits[2..4], &digits[4..6])
+    }
+
+    /// Returns the active display mode.
+    pub fn mode(&self) -> DisplayMode {
+        self.mode.borrow().mode()
+    }
+
+    /// Returns whether the timer or stopwatch is counting.
+    pub fn is_running(&self) -> bool {
+        self.mode.borrow().is_running()
+    }
+
+    /// Switches the display mode, leaving the timer or stopwatch stopped.
+    ///
+    /// Any scrolling message is dismissed so the new mode shows right away.
+    pub fn set_mode(&self, mode: DisplayMode) {
+        self.mode.borrow_mut().set_mode(mode);
+        self.show_mode_change();
+    }
+
+    /// Switches to timer mode and starts counting down from `duration`.
+    pub fn start_timer(&self, duration: Duration) {
+        self.mode.borrow_mut().start_timer(duration, Instant::now());
+        self.show_mode_change();
+    }
+
+    /// Starts or pauses the timer or stopwatch.
+    pub fn toggle_running(&self) {
+        self.mode.borrow_mut().toggle_running(Instant::now());
+        self.show_mode_change();
+    }
+
+    /// Stops the timer or stopwatch and returns it to its starting value.
+    pub fn reset_mode(&self) {
+        self.mode.borrow_mut().reset();
+        self.show_mode_change();
+    }
+
+    /// Carries the mode and timer state over from a display being replaced.
+    ///
+    /// Used when the display is rebuilt after a config reload so a running
+    /// timer or stopwatch keeps counting.
+    pub fn inherit_state(&self, previous: &ClockDisplay) {
+        *self.mode.borrow_mut() = previous.mode.borrow().clone();
+    }
+
+    /// Dismisses any message and shows the current mode's reading.
+    fn show_mode_change(&self) {
+        self.marquee.borrow_mut().take();
+        Self::show_time(
+            &self.digits,
+            &self.separators,
+            &self.mode.borrow(),
+            self.wall_clock.get(),
+            self.ambient.get(),
+            false,
+        );
+    }
+
+    /// Scrolls a message across the display, then returns to the time.
+    ///
+    /// The message enters from the right and scrolls left one clock column
+    /// per step, repeating until the configured duration elapses. Showing a
+    /// new message replaces any message already scrolling.
+    ///
+    /// # Arguments
+    /// * `text` - The message to display
+    pub fn show_message(&self, text: &str) {
+        let display_columns: usize = self.digits.iter().map(|d| d.column_count()).sum();
+
+        let mut columns = vec![BLANK_COLUMN; display_columns];
+        columns.extend(render_text(text));
+
+        let generation = self.marquee_generation.get() + 1;
+        self.marquee_generation.set(generation);
+
+        *self.marquee.borrow_mut() = Some(Marquee {
+            columns,
+            offset: 0,
+            ends_at: Instant::now() + self.marquee_duration.get(),
+            generation,
+        });
+        // The ambient analog phase may have hidden them
+        Self::show_separators(&self.separators, true);
+
+        let digits = Rc::downgrade(&self.digits);
+        let marquee = Rc::downgrade(&self.marquee);
+        let mode = Rc::downgrade(&self.mode);
+        let wall_clock = Rc::downgrade(&self.wall_clock);
+        let ambient = Rc::downgrade(&self.ambient);
+        let separators = self.separators.clone();
+
+        glib::timeout_add_local(self.marquee_step.get(), move || {
+            let (Some(digits), Some(marquee), Some(mode), Some(wall_clock), Some(ambient)) = (
+                digits.upgrade(),
+                marquee.upgrade(),
+                mode.upgrade(),
+                wall_clock.upgrade(),
+                ambient.upgrade(),
+            ) else {
+                return glib::ControlFlow::Break;
+            };
+
+            let mut state = marquee.borrow_mut();
+            let Some(current) = state.as_mut().filter(|m| m.generation == generation) else {
+                return glib::ControlFlow::Break;
+            };
+
+            if Instant::now() >= current.ends_at {
+                *state = None;
+                Self::show_time(
+                    &digits,
+                    &separators,
+                    &mode.borrow(),
+                    wall_clock.get(),
+                    ambient.get(),
+                    false,
+                );
+                return glib::ControlFlow::Break;
+            }
+
+            Self::render_marquee(&digits, current);
+            current.offset = (current.offset + 1) % current.columns.len();
+            glib::ControlFlow::Continue
+        });
+    }
+
+    /// Returns whether a message is currently scrolling.
+    pub fn is_showing_message(&self) -> bool {
+        self.marquee.borrow().is_some()
+    }
+
+    /// Sets every display column to the marquee's current window.
+    fn render_marquee(digits: &[DigitDisplay], marquee: &Marquee) {
+        let mut index = marquee.offset;
+
+        for digit in digits {
+            for col in 0..digit.column_count() {
+                digit.set_column(col, &marquee.columns[index % marquee.columns.len()]);
+                index += 1;
             }
         }
     }
@@ -207,3 +649,34 @@ impl ClockDisplay {
         self.container.upcast_ref()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_requires_rebuild() {
+        let old = Config::default();
+        assert!(!ClockDisplay::requires_rebuild(&old, &old.clone()));
+
+        let mut new = old.clone();
+        new.clock.size += 10;
+        assert!(ClockDisplay::requires_rebuild(&old, &new));
+
+        let mut new = old.clone();
+        new.clock.clock_gap += 1;
+        assert!(ClockDisplay::requires_rebuild(&old, &new));
+
+        let mut new = old.clone();
+        new.clock.digit_gap += 1;
+        assert!(ClockDisplay::requires_rebuild(&old, &new));
+
+        // Everything else is applied in place
+        let mut new = old.clone();
+        new.clock.stroke_width += 1.0;
+        new.clock.animation_duration_ms += 100;
+        new.clock.mode = DisplayMode::Stopwatch;
+        new.colors.clock_hand_color = "#ff0000".to_string();
+        assert!(!ClockDisplay::requires_rebuild(&old, &new));
+    }
+}
@@ -0,0 +1,683 @@
+//! Color parsing and formatting.
+//!
+//! Colors in the config use CSS color syntax, so values from theming tools
+//! such as matugen or pywal can be pasted in as they are:
+//!
+//! - `#RGB`, `#RGBA`, `#RRGGBB` and `#RRGGBBAA` (the `#` may be left out of
+//!   the long forms)
+//! - Named colors such as `tomato` or `rebeccapurple`, and `transparent`
+//! - `rgb()`/`rgba()` with 0-255 or percentage channels
+//! - `hsl()`/`hsla()` with a hue in degrees (or `turn`, `rad`, `grad`)
+//! - `oklch()` with a lightness, chroma and hue
+//!
+//! Functions take comma- or space-separated arguments and an optional
+//! alpha, either as a fourth argument or after a `/` (e.g.
+//! `rgb(255 107 107 / 15%)`). Colors are parsed into Cairo-compatible
+//! `(red, green, blue, alpha)` tuples with each component in 0.0-1.0.
+//!
+//! Config colors can also be expressions over other colors (see
+//! `eval_color`):
+//!
+//! - `$name` - Another color, e.g. `$clock_hand_color` or a palette entry
+//! - `$name / 15%` - The same color with a different alpha
+//! - `alpha(color, 0.15)` - Likewise, for any color
+//! - `lighten(color, 20%)` / `darken(color, 20%)` - Mixed toward white or
+//!   black by the amount
+//! - `mix(color, color, 30%)` - Blended from the first toward the second
+//!   (halfway if the amount is left out)
+//!
+//! Arguments can be expressions themselves.
+
+/// CSS named colors as `(name, 0xRRGGBB)` pairs.
+const NAMED_COLORS: [(&str, u32); 148] = [
+    ("aliceblue", 0xf0f8ff),
+    ("antiquewhite", 0xfaebd7),
+    ("aqua", 0x00ffff),
+    ("aquamarine", 0x7fffd4),
+    ("azure", 0xf0ffff),
+    ("beige", 0xf5f5dc),
+    ("bisque", 0xffe4c4),
+    ("black", 0x000000),
+    ("blanchedalmond", 0xffebcd),
+    ("blue", 0x0000ff),
+    ("blueviolet", 0x8a2be2),
+    ("brown", 0xa52a2a),
+    ("burlywood", 0xdeb887),
+    ("cadetblue", 0x5f9ea0),
+    ("chartreuse", 0x7fff00),
+    ("chocolate", 0xd2691e),
+    ("coral", 0xff7f50),
+    ("cornflowerblue", 0x6495ed),
+    ("cornsilk", 0xfff8dc),
+    ("crimson", 0xdc143c),
+    ("cyan", 0x00ffff),
+    ("darkblue", 0x00008b),
+    ("darkcyan", 0x008b8b),
+    ("darkgoldenrod", 0xb8860b),
+    ("darkgray", 0xa9a9a9),
+    ("darkgreen", 0x006400),
+    ("darkgrey", 0xa9a9a9),
+    ("darkkhaki", 0xbdb76b),
+    ("darkmagenta", 0x8b008b),
+    ("darkolivegreen", 0x556b2f),
+    ("darkorange", 0xff8c00),
+    ("darkorchid", 0x9932cc),
+    ("darkred", 0x8b0000),
+    ("darksalmon", 0xe9967a),
+    ("darkseagreen", 0x8fbc8f),
+    ("darkslateblue", 0x483d8b),
+    ("darkslategray", 0x2f4f4f),
+    ("darkslategrey", 0x2f4f4f),
+    ("darkturquoise", 0x00ced1),
+    ("darkviolet", 0x9400d3),
+    ("deeppink", 0xff1493),
+    ("deepskyblue", 0x00bfff),
+    ("dimgray", 0x696969),
+    ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1e90ff),
+    ("firebrick", 0xb22222),
+    ("floralwhite", 0xfffaf0),
+    ("forestgreen", 0x228b22),
+    ("fuchsia", 0xff00ff),
+    ("gainsboro", 0xdcdcdc),
+    ("ghostwhite", 0xf8f8ff),
+    ("gold", 0xffd700),
+    ("goldenrod", 0xdaa520),
+    ("gray", 0x808080),
+    ("green", 0x008000),
+    ("greenyellow", 0xadff2f),
+    ("grey", 0x808080),
+    ("honeydew", 0xf0fff0),
+    ("hotpink", 0xff69b4),
+    ("indianred", 0xcd5c5c),
+    ("indigo", 0x4b0082),
+    ("ivory", 0xfffff0),
+    ("khaki", 0xf0e68c),
+    ("lavender", 0xe6e6fa),
+    ("lavenderblush", 0xfff0f5),
+    ("lawngreen", 0x7cfc00),
+    ("lemonchiffon", 0xfffacd),
+    ("lightblue", 0xadd8e6),
+    ("lightcoral", 0xf08080),
+    ("lightcyan", 0xe0ffff),
+    ("lightgoldenrodyellow", 0xfafad2),
+    ("lightgray", 0xd3d3d3),
+    ("lightgreen", 0x90ee90),
+    ("lightgrey", 0xd3d3d3),
+    ("lightpink", 0xffb6c1),
+    ("lightsalmon", 0xffa07a),
+    ("lightseagreen", 0x20b2aa),
+    ("lightskyblue", 0x87cefa),
+    ("lightslategray", 0x778899),
+    ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xb0c4de),
+    ("lightyellow", 0xffffe0),
+    ("lime", 0x00ff00),
+    ("limegreen", 0x32cd32),
+    ("linen", 0xfaf0e6),
+    ("magenta", 0xff00ff),
+    ("maroon", 0x800000),
+    ("mediumaquamarine", 0x66cdaa),
+    ("mediumblue", 0x0000cd),
+    ("mediumorchid", 0xba55d3),
+    ("mediumpurple", 0x9370db),
+    ("mediumseagreen", 0x3cb371),
+    ("mediumslateblue", 0x7b68ee),
+    ("mediumspringgreen", 0x00fa9a),
+    ("mediumturquoise", 0x48d1cc),
+    ("mediumvioletred", 0xc71585),
+    ("midnightblue", 0x191970),
+    ("mintcream", 0xf5fffa),
+    ("mistyrose", 0xffe4e1),
+    ("moccasin", 0xffe4b5),
+    ("navajowhite", 0xffdead),
+    ("navy", 0x000080),
+    ("oldlace", 0xfdf5e6),
+    ("olive", 0x808000),
+    ("olivedrab", 0x6b8e23),
+    ("orange", 0xffa500),
+    ("orangered", 0xff4500),
+    ("orchid", 0xda70d6),
+    ("palegoldenrod", 0xeee8aa),
+    ("palegreen", 0x98fb98),
+    ("paleturquoise", 0xafeeee),
+    ("palevioletred", 0xdb7093),
+    ("papayawhip", 0xffefd5),
+    ("peachpuff", 0xffdab9),
+    ("peru", 0xcd853f),
+    ("pink", 0xffc0cb),
+    ("plum", 0xdda0dd),
+    ("powderblue", 0xb0e0e6),
+    ("purple", 0x800080),
+    ("rebeccapurple", 0x663399),
+    ("red", 0xff0000),
+    ("rosybrown", 0xbc8f8f),
+    ("royalblue", 0x4169e1),
+    ("saddlebrown", 0x8b4513),
+    ("salmon", 0xfa8072),
+    ("sandybrown", 0xf4a460),
+    ("seagreen", 0x2e8b57),
+    ("seashell", 0xfff5ee),
+    ("sienna", 0xa0522d),
+    ("silver", 0xc0c0c0),
+    ("skyblue", 0x87ceeb),
+    ("slateblue", 0x6a5acd),
+    ("slategray", 0x708090),
+    ("slategrey", 0x708090),
+    ("snow", 0xfffafa),
+    ("springgreen", 0x00ff7f),
+    ("steelblue", 0x4682b4),
+    ("tan", 0xd2b48c),
+    ("teal", 0x008080),
+    ("thistle", 0xd8bfd8),
+    ("tomato", 0xff6347),
+    ("turquoise", 0x40e0d0),
+    ("violet", 0xee82ee),
+    ("wheat", 0xf5deb3),
+    ("white", 0xffffff),
+    ("whitesmoke", 0xf5f5f5),
+    ("yellow", 0xffff00),
+    ("yellowgreen", 0x9acd32),
+];
+
+/// An RGBA color with each component in 0.0-1.0.
+pub type Rgba = (f64, f64, f64, f64);
+
+/// Parses a color in CSS syntax (see the module docs for the forms).
+///
+/// # Arguments
+/// * `text` - Color string, e.g. `#ff6b6b26`, `tomato` or `hsl(0 100% 71%)`
+///
+/// # Returns
+/// * `Ok((red, green, blue, alpha))` - Components in the range 0.0-1.0
+/// * `Err(String)` - Why the color is invalid
+pub fn parse_color(text: &str) -> Result<Rgba, String> {
+    let invalid = |reason: &str| format!("invalid color '{}' ({})", text, reason);
+    let color = text.trim().to_ascii_lowercase();
+
+    if color.is_empty() {
+        return Err(invalid("empty"));
+    }
+    if color == "transparent" {
+        return Ok((0.0, 0.0, 0.0, 0.0));
+    }
+    if let Some((_, rgb)) = NAMED_COLORS.iter().find(|(name, _)| *name == color) {
+        let channel = |shift: u32| f64::from((rgb >> shift) & 0xff) / 255.0;
+        return Ok((channel(16), channel(8), channel(0), 1.0));
+    }
+    if let Some((function, rest)) = color.split_once('(') {
+        let args = rest
+            .strip_suffix(')')
+            .ok_or_else(|| invalid("missing ')'"))?;
+        return parse_function(function.trim(), args).map_err(|reason| invalid(&reason));
+    }
+    parse_hex(&color).map_err(|reason| invalid(&reason))
+}
+
+/// Parses a color, falling back to black if it is invalid.
+///
+/// Config colors are checked with `parse_color` when loading, so the
+/// fallback only applies to unchecked input, and is reported.
+///
+/// # Returns
+/// Tuple of `(red, green, blue, alpha)` where each value is 0.0-1.0
+pub fn parse_color_or_black(text: &str) -> Rgba {
+    parse_color(text).unwrap_or_else(|e| {
+        eprintln!("Warning: {}, using black", e);
+        (0.0, 0.0, 0.0, 1.0)
+    })
+}
+
+/// Formats RGBA components (0.0-1.0) as `#RRGGBB`, or `#RRGGBBAA` when the
+/// color is translucent.
+pub fn format_hex_color((r, g, b, a): Rgba) -> String {
+    let byte = |channel: f64| (channel.clamp(0.0, 1.0) * 255.0).round() as u8;
+    let rgb = format!("#{:02x}{:02x}{:02x}", byte(r), byte(g), byte(b));
+    match byte(a) {
+        255 => rgb,
+        alpha => format!("{}{:02x}", rgb, alpha),
+    }
+}
+
+/// Evaluates a color expression (see the module docs) to a color.
+///
+/// # Arguments
+/// * `text` - Color or expression, e.g. `$clock_hand_color / 15%`
+/// * `lookup` - Returns the unevaluated value a `$name` refers to
+///
+/// # Returns
+/// * `Ok((red, green, blue, alpha))` - Components in the range 0.0-1.0
+/// * `Err(String)` - Why the expression is invalid, including unknown and
+///   circular references
+pub fn eval_color(text: &str, lookup: &dyn Fn(&str) -> Option<String>) -> Result<Rgba, String> {
+    eval(text, lookup, &mut Vec::new())
+}
+
+/// Evaluates an expression, with `resolving` holding the references being
+/// followed to catch cycles.
+fn eval(
+    text: &str,
+    lookup: &dyn Fn(&str) -> Option<String>,
+    resolving: &mut Vec<String>,
+) -> Result<Rgba, String> {
+    let text = text.trim();
+
+    if let Some(rest) = text.strip_prefix('$') {
+        let end = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-'))
+            .unwrap_or(rest.len());
+        let (name, rest) = rest.split_at(end);
+        if resolving.iter().any(|r| r == name) {
+            return Err(format!("circular color reference '${}'", name));
+        }
+        let value = lookup(name).ok_or_else(|| format!("unknown color reference '${}'", name))?;
+        resolving.push(name.to_string());
+        let color = eval(&value, lookup, resolving);
+        resolving.pop();
+        let color = color?;
+
+        let rest = rest.trim();
+        if rest.is_empty() {
+            return Ok(color);
+        }
+        let alpha = rest
+            .strip_prefix('/')
+            .ok_or_else(|| format!("unexpected '{}' after '${}'", rest, name))?;
+        return Ok(with_alpha(color, amount(alpha.trim())?));
+    }
+
+    if let Some((function, rest)) = text.split_once('(')
+        && let function = function.trim().to_ascii_lowercase()
+        && ["alpha", "lighten", "darken", "mix"].contains(&function.as_str())
+    {
+        let args = rest
+            .strip_suffix(')')
+            .ok_or_else(|| format!("missing ')' in '{}'", text))?;
+        let args = split_args(args);
+        let mut color = |i: usize| eval(args[i], lookup, resolving);
+        return match (function.as_str(), args.len()) {
+            ("alpha", 2) => Ok(with_alpha(color(0)?, amount(args[1])?)),
+            // Toward white or black, keeping the color's alpha
+            ("lighten", 2) => {
+                let color = color(0)?;
+                Ok(blend(color, (1.0, 1.0, 1.0, color.3), amount(args[1])?))
+            }
+            ("darken", 2) => {
+                let color = color(0)?;
+                Ok(blend(color, (0.0, 0.0, 0.0, color.3), amount(args[1])?))
+            }
+            ("mix", 2) => Ok(blend(color(0)?, color(1)?, 0.5)),
+            ("mix", 3) => Ok(blend(color(0)?, color(1)?, amount(args[2])?)),
+            ("mix", _) => Err(format!(
+                "mix() takes 2 colors and an optional amount, in '{}'",
+                text
+            )),
+            _ => Err(format!(
+                "{}() takes a color and an amount, in '{}'",
+                function, text
+            )),
+        };
+    }
+
+    parse_color(text)
+}
+
+/// Splits function arguments at the commas outside of nested parentheses.
+fn split_args(args: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let (mut depth, mut start) = (0, 0);
+    for (i, c) in args.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(args[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(args[start..].trim());
+    parts
+}
+
+/// Parses an amount or alpha, as a number (0.0-1.0) or a percentage.
+fn amount(value: &str) -> Result<f64, String> {
+    Ok(number_or_percentage(value, 1.0)?.clamp(0.0, 1.0))
+}
+
+/// Returns a color with its alpha replaced.
+fn with_alpha((r, g, b, _): Rgba, alpha: f64) -> Rgba {
+    (r, g, b, alpha)
+}
+
+/// Blends two colors by `t` (0.0 = `from`, 1.0 = `to`).
+fn blend(from: Rgba, to: Rgba, t: f64) -> Rgba {
+    (
+        from.0 + (to.0 - from.0) * t,
+        from.1 + (to.1 - from.1) * t,
+        from.2 + (to.2 - from.2) * t,
+        from.3 + (to.3 - from.3) * t,
+    )
+}
+
+/// Parses the hex forms, with the `#` optional on the long ones.
+fn parse_hex(color: &str) -> Result<Rgba, String> {
+    let (digits, short_allowed) = match color.strip_prefix('#') {
+        Some(digits) => (digits, true),
+        None => (color, false),
+    };
+    if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err("not a hex number or color name".to_string());
+    }
+
+    let nibble = |i: usize| u8::from_str_radix(&digits[i..=i], 16).unwrap_or(0);
+    let byte = |i: usize| u8::from_str_radix(&digits[i..i + 2], 16).unwrap_or(0);
+    let bytes = match digits.len() {
+        3 | 4 if short_allowed => (0..digits.len()).map(|i| nibble(i) * 17).collect(),
+        6 | 8 => (0..digits.len()).step_by(2).map(byte).collect::<Vec<u8>>(),
+        _ => return Err("expected #RGB, #RGBA, #RRGGBB or #RRGGBBAA".to_string()),
+    };
+
+    let channel = |i: usize| f64::from(bytes[i]) / 255.0;
+    let alpha = bytes.get(3).map_or(1.0, |a| f64::from(*a) / 255.0);
+    Ok((channel(0), channel(1), channel(2), alpha))
+}
+
+/// Parses the arguments of a color function such as `rgb`.
+fn parse_function(function: &str, args: &str) -> Result<Rgba, String> {
+    // Either `a, b, c[, alpha]` or `a b c[ / alpha]`
+    let (channels, alpha) = match args.split_once('/') {
+        Some((channels, alpha)) => (channels, Some(alpha.trim())),
+        None => (args, None),
+    };
+    let mut values: Vec<&str> = channels
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|value| !value.is_empty())
+        .collect();
+    let alpha = match (alpha, values.len()) {
+        (Some(alpha), 3) => Some(alpha),
+        (None, 4) => values.pop(),
+        (None, 3) => None,
+        _ => {
+            return Err(format!(
+                "{}() takes 3 values and an optional alpha",
+                function
+            ));
+        }
+    };
+    let alpha = match alpha {
+        Some(alpha) => number_or_percentage(alpha, 1.0)?.clamp(0.0, 1.0),
+        None => 1.0,
+    };
+
+    let (r, g, b) = match function {
+        "rgb" | "rgba" => {
+            let channel = |value: &str| {
+                number_or_percentage(value, 255.0).map(|v| (v / 255.0).clamp(0.0, 1.0))
+            };
+            (
+                channel(values[0])?,
+                channel(values[1])?,
+                channel(values[2])?,
+            )
+        }
+        "hsl" | "hsla" => {
+            let hue = angle(values[0])?;
+            let saturation = number_or_percentage(values[1], 100.0)? / 100.0;
+            let lightness = number_or_percentage(values[2], 100.0)? / 100.0;
+            hsl_to_rgb(hue, saturation.clamp(0.0, 1.0), lightness.clamp(0.0, 1.0))
+        }
+        "oklch" => {
+            let lightness = number_or_percentage(values[0], 1.0)?;
+            let chroma = number_or_percentage(values[1], 0.4)?;
+            let hue = angle(values[2])?;
+            oklch_to_rgb(lightness.clamp(0.0, 1.0), chroma.max(0.0), hue)
+        }
+        _ => return Err(format!("unknown color function '{}'", function)),
+    };
+    Ok((r, g, b, alpha))
+}
+
+/// Parses a number, or a percentage of `full`.
+fn number_or_percentage(value: &str, full: f64) -> Result<f64, String> {
+    let (number, percent) = match value.strip_suffix('%') {
+        Some(number) => (number, true),
+        None => (value, false),
+    };
+    number
+        .parse::<f64>()
+        .ok()
+        .filter(|n| n.is_finite())
+        .map(|n| if percent { n * full / 100.0 } else { n })
+        .ok_or_else(|| format!("'{}' is not a number", value))
+}
+
+/// Parses a hue angle into degrees.
+fn angle(value: &str) -> Result<f64, String> {
+    let units = [
+        ("deg", 1.0),
+        ("grad", 0.9),
+        ("rad", 180.0 / std::f64::consts::PI),
+        ("turn", 360.0),
+    ];
+    let (number, scale) = units
+        .iter()
+        .find_map(|(unit, scale)| Some((value.strip_suffix(unit)?, *scale)))
+        .unwrap_or((value, 1.0));
+    number
+        .parse::<f64>()
+        .ok()
+        .filter(|n| n.is_finite())
+        .map(|n| n * scale)
+        .ok_or_else(|| format!("'{}' is not an angle", value))
+}
+
+/// Converts HSL (hue in degrees, saturation and lightness 0.0-1.0) to RGB.
+fn hsl_to_rgb(hue: f64, saturation: f64, lightness: f64) -> (f64, f64, f64) {
+    let channel = |n: f64| {
+        let k = (n + hue.rem_euclid(360.0) / 30.0) % 12.0;
+        let a = saturation * lightness.min(1.0 - lightness);
+        lightness - a * (k - 3.0).min(9.0 - k).clamp(-1.0, 1.0)
+    };
+    (channel(0.0), channel(8.0), channel(4.0))
+}
+
+/// Converts OKLCH to sRGB, clipping colors outside the sRGB gamut.
+fn oklch_to_rgb(lightness: f64, chroma: f64, hue: f64) -> (f64, f64, f64) {
+    let (sin, cos) = hue.to_radians().sin_cos();
+    let (a, b) = (chroma * cos, chroma * sin);
+
+    // OKLab to linear sRGB, via the LMS cone responses
+    let l = (lightness + 0.396_337_777_4 * a + 0.215_803_757_3 * b).powi(3);
+    let m = (lightness - 0.105_561_345_8 * a - 0.063_854_172_8 * b).powi(3);
+    let s = (lightness - 0.089_484_177_5 * a - 1.291_485_548_0 * b).powi(3);
+    let red = 4.076_741_662_1 * l - 3.307_711_591_3 * m + 0.230_969_929_2 * s;
+    let green = -1.268_438_004_6 * l + 2.609_757_401_1 * m - 0.341_319_396_5 * s;
+    let blue = -0.004_196_086_3 * l - 0.703_418_614_7 * m + 1.707_614_701_0 * s;
+
+    let gamma = |linear: f64| {
+        let linear = linear.clamp(0.0, 1.0);
+        if linear <= 0.003_130_8 {
+            12.92 * linear
+        } else {
+            1.055 * linear.powf(1.0 / 2.4) - 0.055
+        }
+    };
+    (gamma(red), gamma(green), gamma(blue))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(text: &str) -> String {
+        format_hex_color(parse_color(text).unwrap())
+    }
+
+    #[test]
+    fn test_parse_hex_color_rgb() {
+        // Test standard RGB format
+        let (r, g, b, a) = parse_color_or_black("#ff0000");
+        assert_eq!(r, 1.0);
+        assert_eq!(g, 0.0);
+        assert_eq!(b, 0.0);
+        assert_eq!(a, 1.0);
+    }
+
+    #[test]
+    fn test_parse_hex_color_rgba() {
+        // Test RGBA format with transparency
+        let (r, g, b, a) = parse_color_or_black("#ff6b6b26");
+        assert_eq!(r, 1.0);
+        assert!((g - 0.4196).abs() < 0.01); // 0x6b = 107 / 255 ≈ 0.4196
+        assert!((b - 0.4196).abs() < 0.01);
+        assert!((a - 0.1490).abs() < 0.01); // 0x26 = 38 / 255 ≈ 0.149
+    }
+
+    #[test]
+    fn test_parse_hex_color_no_hash() {
+        // Test without leading #
+        let (r, g, b, a) = parse_color_or_black("00ff00");
+        assert_eq!(r, 0.0);
+        assert_eq!(g, 1.0);
+        assert_eq!(b, 0.0);
+        assert_eq!(a, 1.0);
+    }
+
+    #[test]
+    fn test_parse_hex_color_black() {
+        let (r, g, b, a) = parse_color_or_black("#000000");
+        assert_eq!(r, 0.0);
+        assert_eq!(g, 0.0);
+        assert_eq!(b, 0.0);
+        assert_eq!(a, 1.0);
+    }
+
+    #[test]
+    fn test_parse_hex_color_white() {
+        let (r, g, b, a) = parse_color_or_black("#ffffff");
+        assert_eq!(r, 1.0);
+        assert_eq!(g, 1.0);
+        assert_eq!(b, 1.0);
+        assert_eq!(a, 1.0);
+    }
+
+    #[test]
+    fn test_parse_hex_color_fully_transparent() {
+        let (r, g, b, a) = parse_color_or_black("#ff000000");
+        assert_eq!(r, 1.0);
+        assert_eq!(g, 0.0);
+        assert_eq!(b, 0.0);
+        assert_eq!(a, 0.0);
+    }
+
+    #[test]
+    fn test_parse_hex_color_invalid_length() {
+        // Invalid length should default to black
+        let (r, g, b, a) = parse_color_or_black("#ff");
+        assert_eq!(r, 0.0);
+        assert_eq!(g, 0.0);
+        assert_eq!(b, 0.0);
+        assert_eq!(a, 1.0);
+    }
+
+    #[test]
+    fn test_parse_color_shorthand_and_names() {
+        assert_eq!(hex("#f80"), "#ff8800");
+        assert_eq!(hex("#f808"), "#ff880088");
+        assert_eq!(hex("Tomato"), "#ff6347");
+        assert_eq!(hex(" rebeccapurple "), "#663399");
+        assert_eq!(parse_color("transparent"), Ok((0.0, 0.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn test_parse_color_functions() {
+        assert_eq!(hex("rgb(255, 107, 107)"), "#ff6b6b");
+        assert_eq!(hex("rgba(255, 107, 107, 0.15)"), "#ff6b6b26");
+        assert_eq!(hex("rgb(255 107 107 / 15%)"), "#ff6b6b26");
+        assert_eq!(hex("rgb(100% 0% 50%)"), "#ff0080");
+        assert_eq!(hex("hsl(0, 100%, 50%)"), "#ff0000");
+        assert_eq!(hex("hsl(120deg 100% 25%)"), "#008000");
+        assert_eq!(hex("hsla(0.5turn, 100%, 50%, 0.5)"), "#00ffff80");
+        assert_eq!(hex("oklch(0% 0 0)"), "#000000");
+        assert_eq!(hex("oklch(1 0 0)"), "#ffffff");
+        // sRGB red is oklch(62.8% 0.2577 29.23)
+        assert_eq!(hex("oklch(62.8% 0.2577 29.23deg)"), "#ff0000");
+    }
+
+    #[test]
+    fn test_parse_color_invalid() {
+        assert!(parse_color("#fffff").is_err());
+        assert!(parse_color("#gg0000").is_err());
+        assert!(parse_color("#ff00zz80").is_err());
+        assert!(parse_color("fff").is_err());
+        assert!(parse_color("").is_err());
+        assert!(parse_color("notacolor").is_err());
+        assert!(parse_color("rgb(1, 2)").is_err());
+        assert!(parse_color("rgb(1, 2, x)").is_err());
+        assert!(parse_color("rgb(1, 2, 3").is_err());
+        assert!(parse_color("cmyk(1, 2, 3)").is_err());
+        assert_eq!(parse_color("#00ff00"), Ok((0.0, 1.0, 0.0, 1.0)));
+        assert_eq!(
+            parse_color("rgb(1, 2)"),
+            Err(
+                "invalid color 'rgb(1, 2)' (rgb() takes 3 values and an optional alpha)"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn test_format_hex_color() {
+        assert_eq!(format_hex_color((1.0, 0.0, 0.0, 1.0)), "#ff0000");
+        assert_eq!(
+            format_hex_color(parse_color_or_black("#ff6b6b26")),
+            "#ff6b6b26"
+        );
+        assert_eq!(format_hex_color((2.0, -1.0, 0.5, 0.0)), "#ff008000");
+    }
+
+    #[test]
+    fn test_eval_color() {
+        let lookup = |name: &str| {
+            match name {
+                "accent" => Some("#ff6b6b"),
+                "inactive" => Some("$accent / 15%"),
+                "loop" => Some("lighten($loop, 10%)"),
+                _ => None,
+            }
+            .map(str::to_string)
+        };
+        let eval = |text: &str| eval_color(text, &lookup).map(format_hex_color);
+
+        assert_eq!(eval("tomato"), Ok("#ff6347".to_string()));
+        assert_eq!(eval("$accent"), Ok("#ff6b6b".to_string()));
+        assert_eq!(eval("$inactive"), Ok("#ff6b6b26".to_string()));
+        assert_eq!(eval("$inactive / 1"), Ok("#ff6b6b".to_string()));
+        assert_eq!(eval("alpha($accent, 0.1)"), Ok("#ff6b6b1a".to_string()));
+        assert_eq!(eval("lighten(#000000, 50%)"), Ok("#808080".to_string()));
+        assert_eq!(eval("darken(#ffffff80, 0.5)"), Ok("#80808080".to_string()));
+        assert_eq!(eval("mix(#000000, #ffffff)"), Ok("#808080".to_string()));
+        assert_eq!(
+            eval("mix($accent, rgb(0, 0, 255), 100%)"),
+            Ok("#0000ff".to_string())
+        );
+        assert_eq!(
+            eval("alpha(mix(black, white, 0), 0)"),
+            Ok("#00000000".to_string())
+        );
+
+        assert_eq!(
+            eval("$missing"),
+            Err("unknown color reference '$missing'".to_string())
+        );
+        assert_eq!(
+            eval("$loop"),
+            Err("circular color reference '$loop'".to_string())
+        );
+        assert!(eval("$accent 50%").is_err());
+        assert!(eval("mix($accent)").is_err());
+        assert!(eval("alpha($accent, x)").is_err());
+    }
+}
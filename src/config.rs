@@ -4,7 +4,13 @@
 //! platform-specific directories. It provides:
 //! - Structured configuration with sensible defaults
 //! - TOML deserialization with `#[serde(default)]` for graceful partial configs
-//! - Hex color parsing (#RRGGBB or #RRGGBBAA) to Cairo RGBA values
+//! - `[[colors.schedule]]` entries changing colors over the day (see `schedule`)
+//! - Color references such as `$accent / 15%`, resolved once merged (see `color`)
+//! - Validation of every file and the merged result, reported as
+//!   `Diagnostic`s with file, line and column
+//! - `ConfigSource`: the file to read plus overrides from the command line
+//!   and IPC, which take precedence over the main file and its includes
+//! - The `[theme]` preset, layered underneath the config files (see `theme`)
 //!
 //! # Configuration Location
 //! - Linux: `~/.config/chronomatrix/config.toml`
@@ -25,58 +31,552 @@
 //! animation_duration_ms = 400
 //! ```
 
+use chrono_tz::Tz;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use toml::value::Table;
 
+use crate::color::{eval_color, format_hex_color};
+use crate::diagnostics::{ConfigError, Diagnostic, Severity, key_span, unknown_keys};
+use crate::schedule::parse_time_of_day;
+use crate::theme::{self, DEFAULT_THEME, SystemStyle, ThemeSource};
+
 /// Result of loading configuration, including all source file paths for hot-reload watching.
+#[derive(Debug)]
 pub struct ConfigLoadResult {
     pub config: Config,
     /// All resolved file paths that contributed to this config (main + includes).
     pub source_files: Vec<PathBuf>,
+    /// Problems found while loading; only warnings unless defaults were used
+    pub diagnostics: Vec<Diagnostic>,
+    /// Resolved path of `window.custom_css`, if set
+    pub custom_css: Option<PathBuf>,
+    /// The selected theme's file, if it is a user theme
+    pub theme_file: Option<PathBuf>,
+    /// Resolved paths of the background images that are set
+    pub images: Vec<PathBuf>,
+}
+
+/// Where the configuration comes from.
+///
+/// Precedence, highest first: `overrides`, then the main file's includes,
+/// then the main file itself, then built-in defaults.
+#[derive(Debug, Clone, Default)]
+pub struct ConfigSource {
+    /// Config file to read, or `None` for `Config::default_path()`
+    pub path: Option<PathBuf>,
+    /// Settings from the command line or IPC, merged over the files
+    pub overrides: Table,
+    /// Desktop appearance, used when `theme.follow_system` is set
+    pub system: SystemStyle,
+}
+
+impl ConfigSource {
+    /// Returns the config file to read.
+    pub fn path(&self) -> PathBuf {
+        self.path.clone().unwrap_or_else(Config::default_path)
+    }
+
+    /// Sets a single override, e.g. `clock.format`.
+    ///
+    /// # Arguments
+    /// * `section` - Config section (table) name, e.g. `"clock"`
+    /// * `key` - Field within the section
+    /// * `value` - Value that replaces whatever the files set
+    pub fn set(&mut self, section: &str, key: &str, value: toml::Value) {
+        let table = self
+            .overrides
+            .entry(section)
+            .or_insert_with(|| toml::Value::Table(Table::new()));
+        if let toml::Value::Table(table) = table {
+            table.insert(key.to_string(), value);
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(default)]
 pub struct Config {
+    #[serde(default)]
+    pub theme: ThemeConfig,
     #[serde(default)]
     pub colors: ColorConfig,
     #[serde(default)]
     pub window: WindowConfig,
     #[serde(default)]
+    pub display: DisplayConfig,
+    #[serde(default)]
     pub clock: ClockConfig,
+    #[serde(default)]
+    pub face: FaceConfig,
+    #[serde(default)]
+    pub hands: HandsConfig,
+    #[serde(default)]
+    pub effects: EffectsConfig,
+    #[serde(default)]
+    pub marquee: MarqueeConfig,
+    #[serde(default)]
+    pub ambient: AmbientConfig,
+    #[serde(default)]
+    pub shortcuts: ShortcutConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ThemeConfig {
+    /// Built-in theme, or a user theme from the `themes` directory next to
+    /// the main config file
+    pub name: String,
+    /// Pick `light` or `dark` by the desktop's preference instead of `name`,
+    /// and take the hand and separator colors from its accent color
+    pub follow_system: bool,
+    /// Theme used with `follow_system` while the desktop prefers light colors
+    pub light: String,
+    /// Theme used with `follow_system` while the desktop prefers dark colors
+    pub dark: String,
+}
+
+impl ThemeConfig {
+    /// Returns the config key naming the theme in use, and its value.
+    ///
+    /// # Arguments
+    /// * `system` - Desktop appearance, consulted only with `follow_system`
+    pub fn selected(&self, system: &SystemStyle) -> (&'static str, &str) {
+        match (self.follow_system, system.dark) {
+            (false, _) => ("theme.name", &self.name),
+            (true, false) => ("theme.light", &self.light),
+            (true, true) => ("theme.dark", &self.dark),
+        }
+    }
+
+    /// Reads the `[theme]` section of a merged config table, falling back to
+    /// defaults if it is missing or malformed.
+    fn from_table(table: &Table) -> Self {
+        table
+            .get("theme")
+            .cloned()
+            .and_then(|theme| theme.try_into().ok())
+            .unwrap_or_default()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct ColorConfig {
-    /// Window background color (hex format: #RRGGBB or #RRGGBBAA)
+    /// Window background color (CSS color, e.g. #RRGGBB or #RRGGBBAA)
     pub window_background: String,
-    /// Active clock hand color (hex format: #RRGGBB or #RRGGBBAA)
+    /// Active clock hand color (CSS color, e.g. #RRGGBB or #RRGGBBAA)
     pub clock_hand_color: String,
-    /// Inactive clock hand color (hex format: #RRGGBB or #RRGGBBAA)
+    /// Inactive clock hand color (CSS color, e.g. #RRGGBB or #RRGGBBAA)
     pub clock_hand_inactive: String,
-    /// Clock background color (hex format: #RRGGBB or #RRGGBBAA)
+    /// Clock background color (CSS color, e.g. #RRGGBB or #RRGGBBAA)
     pub clock_bg: String,
-    /// Clock border color (hex format: #RRGGBB or #RRGGBBAA)
+    /// Clock border color (CSS color, e.g. #RRGGBB or #RRGGBBAA)
     pub clock_border: String,
-    /// Display container background color (hex format: #RRGGBB or #RRGGBBAA)
+    /// Display container background color (CSS color, e.g. #RRGGBB or #RRGGBBAA)
     pub display_bg: String,
-    /// Display container border color (hex format: #RRGGBB or #RRGGBBAA)
+    /// Display container border color (CSS color, e.g. #RRGGBB or #RRGGBBAA)
     pub display_border: String,
-    /// Separator dot color (hex format: #RRGGBB or #RRGGBBAA)
+    /// Separator dot color (CSS color, e.g. #RRGGBB or #RRGGBBAA)
     pub separator_color: String,
+    /// Color of the hands' drop shadow (CSS color, e.g. #RRGGBB or #RRGGBBAA)
+    pub hand_shadow: String,
+    /// Colors taking effect at times of day, in any order
+    pub schedule: Vec<ColorSchedulePoint>,
+    /// Whether scheduled colors blend into each other or switch at once
+    pub schedule_mode: ScheduleMode,
+    /// Hand colors of the hour digits, over the ones above
+    pub hours: GroupColors,
+    /// Hand colors of the minute digits, over the ones above
+    pub minutes: GroupColors,
+    /// Hand colors of the second digits, over the ones above
+    pub seconds: GroupColors,
+    /// Colors of every hour hand, beneath the digit colors
+    pub hour_hand: HandColors,
+    /// Colors of every minute hand, beneath the digit colors
+    pub minute_hand: HandColors,
+    /// Named colors for other colors to refer to as `$name`
+    pub palette: BTreeMap<String, String>,
+}
+
+/// Names of the color settings in `[colors]`, in display order.
+pub const COLOR_KEYS: [&str; 9] = [
+    "window_background",
+    "clock_hand_color",
+    "clock_hand_inactive",
+    "clock_bg",
+    "clock_border",
+    "display_bg",
+    "display_border",
+    "separator_color",
+    "hand_shadow",
+];
+
+impl ColorConfig {
+    /// Returns the color setting named `key` (see `COLOR_KEYS`).
+    pub fn get(&self, key: &str) -> Option<&String> {
+        match key {
+            "window_background" => Some(&self.window_background),
+            "clock_hand_color" => Some(&self.clock_hand_color),
+            "clock_hand_inactive" => Some(&self.clock_hand_inactive),
+            "clock_bg" => Some(&self.clock_bg),
+            "clock_border" => Some(&self.clock_border),
+            "display_bg" => Some(&self.display_bg),
+            "display_border" => Some(&self.display_border),
+            "separator_color" => Some(&self.separator_color),
+            "hand_shadow" => Some(&self.hand_shadow),
+            _ => None,
+        }
+    }
+
+    /// Returns the color setting named `key` for changing it.
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut String> {
+        match key {
+            "window_background" => Some(&mut self.window_background),
+            "clock_hand_color" => Some(&mut self.clock_hand_color),
+            "clock_hand_inactive" => Some(&mut self.clock_hand_inactive),
+            "clock_bg" => Some(&mut self.clock_bg),
+            "clock_border" => Some(&mut self.clock_border),
+            "display_bg" => Some(&mut self.display_bg),
+            "display_border" => Some(&mut self.display_border),
+            "separator_color" => Some(&mut self.separator_color),
+            "hand_shadow" => Some(&mut self.hand_shadow),
+            _ => None,
+        }
+    }
+
+    /// Returns the colors of one hand of a digit, with the most specific
+    /// setting winning: `[colors.<group>.tens|ones]`, then
+    /// `[colors.<group>]`, then `[colors.hour_hand|minute_hand]`, then
+    /// `[colors]`.
+    ///
+    /// # Arguments
+    /// * `index` - Digit position, 0-5 from the left of `HH:MM:SS`
+    /// * `hand` - Which hand of the digit's clocks
+    ///
+    /// # Returns
+    /// The `(active, inactive)` hand colors
+    pub fn digit_hand_colors(&self, index: usize, hand: Hand) -> (&str, &str) {
+        let group = match index / 2 {
+            0 => &self.hours,
+            1 => &self.minutes,
+            _ => &self.seconds,
+        };
+        let digit = if index.is_multiple_of(2) {
+            &group.tens
+        } else {
+            &group.ones
+        };
+        let hand = match hand {
+            Hand::Hour => &self.hour_hand,
+            Hand::Minute => &self.minute_hand,
+        };
+
+        (
+            first_set([
+                &digit.clock_hand_color,
+                &group.clock_hand_color,
+                &hand.clock_hand_color,
+                &self.clock_hand_color,
+            ]),
+            first_set([
+                &digit.clock_hand_inactive,
+                &group.clock_hand_inactive,
+                &hand.clock_hand_inactive,
+                &self.clock_hand_inactive,
+            ]),
+        )
+    }
+
+    /// Evaluates a color or color expression against these colors.
+    ///
+    /// `$name` refers to a `palette` entry, or else to a color setting
+    /// such as `clock_hand_color`, as written (see `color::eval_color`).
+    ///
+    /// # Returns
+    /// * `Ok((red, green, blue, alpha))` - Components in the range 0.0-1.0
+    /// * `Err(String)` - Why the color is invalid
+    pub fn eval(&self, text: &str) -> Result<(f64, f64, f64, f64), String> {
+        eval_color(text, &|name| self.lookup(name).cloned())
+    }
+
+    /// Returns the color `$name` refers to, unevaluated.
+    fn lookup(&self, name: &str) -> Option<&String> {
+        self.palette.get(name).or_else(|| self.get(name))
+    }
+
+    /// Checks one color setting as written, reporting only its own problems.
+    ///
+    /// A color that refers to an invalid one is not reported again, unless
+    /// it is part of a reference cycle, which has no single place to report.
+    ///
+    /// # Arguments
+    /// * `key` - Dotted key of the setting, as listed by `settings`
+    /// * `text` - The setting's value
+    fn check(&self, key: &str, text: &str) -> Result<(), String> {
+        // Every known reference stands in for a valid color
+        eval_color(text, &|name| {
+            self.lookup(name).map(|_| "#000000".to_string())
+        })?;
+
+        let name = key.strip_prefix("colors.palette.").or_else(|| {
+            key.strip_prefix("colors.")
+                .filter(|name| COLOR_KEYS.contains(name) && !self.palette.contains_key(*name))
+        });
+        let in_cycle = name.is_some_and(|name| {
+            self.eval(&format!("${}", name))
+                .is_err_and(|e| e == format!("circular color reference '${}'", name))
+        });
+        match self.eval(text) {
+            Err(e) if in_cycle => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    /// Replaces every color with the one it evaluates to, so expressions
+    /// become plain `#RRGGBB` or `#RRGGBBAA` colors. Invalid and empty
+    /// colors are left as written.
+    pub fn resolve(&mut self) {
+        let written = self.clone();
+        let resolve = |color: &mut String| {
+            if let Ok(value) = written.eval(color) {
+                *color = format_hex_color(value);
+            }
+        };
+
+        for key in COLOR_KEYS {
+            if let Some(color) = self.get_mut(key) {
+                resolve(color);
+            }
+        }
+        self.palette.values_mut().for_each(resolve);
+        for group in [&mut self.hours, &mut self.minutes, &mut self.seconds] {
+            resolve(&mut group.clock_hand_color);
+            resolve(&mut group.clock_hand_inactive);
+            for digit in [&mut group.tens, &mut group.ones] {
+                resolve(&mut digit.clock_hand_color);
+                resolve(&mut digit.clock_hand_inactive);
+            }
+        }
+        for hand in [&mut self.hour_hand, &mut self.minute_hand] {
+            resolve(&mut hand.clock_hand_color);
+            resolve(&mut hand.clock_hand_inactive);
+        }
+        for point in &mut self.schedule {
+            point.colors.values_mut().for_each(resolve);
+        }
+    }
+
+    /// Returns every color setting as a `(dotted key, value)` pair: the
+    /// `COLOR_KEYS`, the palette, the digit groups' colors that are set and
+    /// the schedule's.
+    fn settings(&self) -> Vec<(String, &String)> {
+        let mut settings: Vec<(String, &String)> = COLOR_KEYS
+            .iter()
+            .filter_map(|key| Some((format!("colors.{}", key), self.get(key)?)))
+            .collect();
+
+        for (name, color) in &self.palette {
+            settings.push((format!("colors.palette.{}", name), color));
+        }
+        let mut tables = Vec::new();
+        for (name, group) in [
+            ("hours", &self.hours),
+            ("minutes", &self.minutes),
+            ("seconds", &self.seconds),
+        ] {
+            tables.push((
+                format!("colors.{}", name),
+                &group.clock_hand_color,
+                &group.clock_hand_inactive,
+            ));
+            for (digit, hand) in [("tens", &group.tens), ("ones", &group.ones)] {
+                tables.push((
+                    format!("colors.{}.{}", name, digit),
+                    &hand.clock_hand_color,
+                    &hand.clock_hand_inactive,
+                ));
+            }
+        }
+        for (name, hand) in [
+            ("hour_hand", &self.hour_hand),
+            ("minute_hand", &self.minute_hand),
+        ] {
+            tables.push((
+                format!("colors.{}", name),
+                &hand.clock_hand_color,
+                &hand.clock_hand_inactive,
+            ));
+        }
+        for (table, active, inactive) in tables {
+            for (key, color) in [
+                ("clock_hand_color", active),
+                ("clock_hand_inactive", inactive),
+            ] {
+                if !color.is_empty() {
+                    settings.push((format!("{}.{}", table, key), color));
+                }
+            }
+        }
+        for (i, point) in self.schedule.iter().enumerate() {
+            for (key, color) in &point.colors {
+                settings.push((format!("colors.schedule.{}.{}", i, key), color));
+            }
+        }
+
+        settings
+    }
+}
+
+/// Returns the first non-empty color, or the last one.
+fn first_set(colors: [&String; 4]) -> &str {
+    colors
+        .iter()
+        .find(|color| !color.is_empty())
+        .unwrap_or(&colors[3])
+}
+
+/// Hand colors of one group of digits, e.g. `[colors.seconds]`.
+///
+/// Empty colors fall back to `[colors]`. `tens` and `ones` override the
+/// group for its left and right digit.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GroupColors {
+    /// Active clock hand color (empty for the `[colors]` one)
+    pub clock_hand_color: String,
+    /// Inactive clock hand color (empty for the `[colors]` one)
+    pub clock_hand_inactive: String,
+    /// Overrides for the left digit of the group
+    pub tens: HandColors,
+    /// Overrides for the right digit of the group
+    pub ones: HandColors,
+}
+
+/// Hand colors of a single digit, e.g. `[colors.seconds.ones]`, or of one
+/// of the hands, e.g. `[colors.hour_hand]`.
+///
+/// Empty colors fall back to the less specific settings (see
+/// `ColorConfig::digit_hand_colors`).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HandColors {
+    /// Active clock hand color (empty to fall back)
+    pub clock_hand_color: String,
+    /// Inactive clock hand color (empty to fall back)
+    pub clock_hand_inactive: String,
+}
+
+/// One of the two hands of an analog clock.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Hand {
+    Hour,
+    Minute,
+}
+
+/// One `[[colors.schedule]]` entry: colors that apply from a time of day.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ColorSchedulePoint {
+    /// Time of day as `HH:MM` (24-hour)
+    pub time: String,
+    /// Color settings from `[colors]` and their values at `time`
+    #[serde(flatten)]
+    pub colors: BTreeMap<String, String>,
+}
+
+/// How colors change between `[[colors.schedule]]` entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScheduleMode {
+    /// Blend continuously from each entry's colors to the next one's
+    #[default]
+    Smooth,
+    /// Keep each entry's colors until the next entry's time
+    Step,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct WindowConfig {
-    /// Enable window transparency (deprecated, kept for compatibility)
-    #[serde(default)]
+    /// Make the window fully transparent, without background or shadow
     pub transparent: bool,
+    /// Let pointer input pass through to the windows below (needs `transparent`)
+    pub click_through: bool,
     /// Window opacity (0.0 - 1.0)
     pub opacity: f64,
+    /// How the window is shown: normal, fullscreen or as a desktop widget
+    pub mode: WindowMode,
+    /// Screen corner or edge a widget is placed at
+    pub anchor: Anchor,
+    /// Distance of a widget from the screen edges it is anchored to, in pixels
+    pub margin: i32,
+    /// CSS file loaded after the generated styles, relative to the main
+    /// config's directory or absolute (empty for none)
+    pub custom_css: String,
+    /// Gradient and image over `colors.window_background`
+    pub background: BackgroundConfig,
+}
+
+/// The `.clock-display` container around the digits.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DisplayConfig {
+    /// Space between the container's border and the digits in pixels
+    pub padding: i32,
+    /// Radius of the container's corners in pixels
+    pub border_radius: i32,
+    /// Width of the container's border in pixels (0 for none)
+    pub border_width: i32,
+    /// Gradient and image over `colors.display_bg`
+    pub background: BackgroundConfig,
+}
+
+/// A gradient and an image drawn over a background color, image on top.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BackgroundConfig {
+    /// Kind of gradient, if any
+    pub gradient: Gradient,
+    /// Colors of the gradient, evenly spaced (at least two)
+    pub gradient_colors: Vec<String>,
+    /// Direction of a linear gradient in degrees: 0 is bottom to top, 90
+    /// left to right, 180 top to bottom
+    pub gradient_angle: f64,
+    /// Image file, relative to the main config's directory or absolute
+    /// (empty for none)
+    pub image: String,
+    /// How the image fills the background
+    pub image_mode: ImageMode,
+}
+
+/// Shape of a background gradient.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Gradient {
+    /// No gradient
+    #[default]
+    None,
+    /// Colors change along a line at `gradient_angle`
+    Linear,
+    /// Colors change outward from the center
+    Radial,
+}
+
+/// How a background image is sized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImageMode {
+    /// Scaled to cover the whole background, cropping the overflow
+    #[default]
+    Cover,
+    /// Scaled to fit inside the background
+    Contain,
+    /// Stretched to the background's size
+    Stretch,
+    /// Repeated at its own size
+    Tile,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -92,6 +592,373 @@ pub struct ClockConfig {
     pub digit_gap: i32,
     /// Animation duration in milliseconds for hand rotation
     pub animation_duration_ms: u64,
+    /// Display mode at startup
+    pub mode: DisplayMode,
+    /// Hour format of the wall clock
+    pub format: TimeFormat,
+    /// IANA timezone for the wall clock, e.g. "Europe/Berlin" (empty for local time)
+    pub timezone: String,
+}
+
+/// How the face of each analog clock is drawn.
+///
+/// The settings apply to every clock; `active` and `inactive` override them
+/// for the clocks forming digits and the resting background clocks.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FaceConfig {
+    /// Shape of the face
+    pub style: FaceStyle,
+    /// Tick marks around the edge of the face
+    pub ticks: TickMarks,
+    /// Shading that makes the face look sunk in or raised
+    pub bevel: Bevel,
+    /// Width of the face's border in pixels (0 for none)
+    pub border_width: f64,
+    /// Overrides for clocks forming digits
+    pub active: FaceOverrides,
+    /// Overrides for resting clocks
+    pub inactive: FaceOverrides,
+}
+
+/// Face settings of one clock state, each falling back to `[face]`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FaceOverrides {
+    pub style: Option<FaceStyle>,
+    pub ticks: Option<TickMarks>,
+    pub bevel: Option<Bevel>,
+    pub border_width: Option<f64>,
+}
+
+/// A clock face as drawn, with the overrides for its state applied.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Face {
+    pub style: FaceStyle,
+    pub ticks: TickMarks,
+    pub bevel: Bevel,
+    pub border_width: f64,
+}
+
+impl FaceConfig {
+    /// Returns the face of clocks in one state.
+    ///
+    /// # Arguments
+    /// * `active` - Whether the clocks form digits
+    pub fn face(&self, active: bool) -> Face {
+        let overrides = if active { &self.active } else { &self.inactive };
+        Face {
+            style: overrides.style.unwrap_or(self.style),
+            ticks: overrides.ticks.unwrap_or(self.ticks),
+            bevel: overrides.bevel.unwrap_or(self.bevel),
+            border_width: overrides.border_width.unwrap_or(self.border_width),
+        }
+    }
+}
+
+/// Shape of a clock face.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FaceStyle {
+    #[default]
+    Circle,
+    Square,
+    RoundedSquare,
+    /// Only the hands, without a face
+    None,
+}
+
+/// Tick marks drawn inside the edge of a clock face.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TickMarks {
+    #[default]
+    None,
+    /// At 12, 3, 6 and 9 o'clock
+    Quarters,
+    /// At every hour
+    Hours,
+}
+
+/// Shading of a clock face.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Bevel {
+    #[default]
+    None,
+    /// An inner shadow along the top edge, as if the face were recessed
+    Inset,
+    /// Light from the top left and shade at the bottom right
+    Raised,
+}
+
+/// Most copies `effects.trail_length` may ask for, bounding the drawing work
+/// per frame
+pub const MAX_TRAIL_LENGTH: u32 = 30;
+
+/// Glow, shadow and motion trail drawn around the clock hands.
+///
+/// Each is drawn as extra strokes of every hand per frame, so they are off
+/// by default.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EffectsConfig {
+    /// How far the glow around active hands reaches, in pixels (0 for none)
+    pub glow_radius: f64,
+    /// Opacity of the glow next to the hand (0.0 - 1.0)
+    pub glow_intensity: f64,
+    /// Whether a drop shadow in `colors.hand_shadow` is drawn under all hands
+    pub shadow: bool,
+    /// Horizontal offset of the shadow in pixels
+    pub shadow_offset_x: f64,
+    /// Vertical offset of the shadow in pixels
+    pub shadow_offset_y: f64,
+    /// How far the shadow's edge is softened, in pixels
+    pub shadow_blur: f64,
+    /// Number of fading copies trailing a moving hand, one per past frame
+    /// (0 for none)
+    pub trail_length: u32,
+    /// Opacity of the newest trail copy at full speed (0.0 - 1.0)
+    pub trail_intensity: f64,
+}
+
+/// Shapes of the hour and minute hands.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HandsConfig {
+    pub hour: HandStyle,
+    pub minute: HandStyle,
+}
+
+impl HandsConfig {
+    /// Returns the shape of one hand.
+    pub fn get(&self, hand: Hand) -> &HandStyle {
+        match hand {
+            Hand::Hour => &self.hour,
+            Hand::Minute => &self.minute,
+        }
+    }
+}
+
+/// Shape of one clock hand, e.g. `[hands.hour]`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HandStyle {
+    /// Length relative to a full-length hand, which ends just inside the face
+    pub length: f64,
+    /// Width in pixels (0 for `clock.stroke_width`)
+    pub width: f64,
+    /// Shape of the hand's ends
+    pub cap: HandCap,
+    /// How much narrower the tip is than the base, from 0.0 (not at all) to
+    /// 1.0 (a point)
+    pub taper: f64,
+    /// Length of a counterweight behind the center, relative to the hand's
+    /// length (0 for none)
+    pub tail: f64,
+}
+
+/// Shape of the ends of a clock hand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HandCap {
+    #[default]
+    Round,
+    /// Cut off square at the ends
+    Butt,
+    /// Cut off square, half the width past the ends
+    Square,
+}
+
+/// What the digits currently represent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DisplayMode {
+    /// The wall-clock time
+    #[default]
+    Clock,
+    /// Time remaining on a countdown
+    Timer,
+    /// Time elapsed since the stopwatch started
+    Stopwatch,
+    /// The wall-clock time on the hands of every clock, and now and then
+    /// in digits
+    Ambient,
+}
+
+impl DisplayMode {
+    /// Returns the name used in actions and on the command line.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DisplayMode::Clock => "clock",
+            DisplayMode::Timer => "timer",
+            DisplayMode::Stopwatch => "stopwatch",
+            DisplayMode::Ambient => "ambient",
+        }
+    }
+}
+
+impl std::fmt::Display for DisplayMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl std::str::FromStr for DisplayMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "clock" => Ok(DisplayMode::Clock),
+            "timer" => Ok(DisplayMode::Timer),
+            "stopwatch" => Ok(DisplayMode::Stopwatch),
+            "ambient" => Ok(DisplayMode::Ambient),
+            other => Err(format!(
+                "Unknown mode '{}' (expected clock, timer, stopwatch or ambient)",
+                other
+            )),
+        }
+    }
+}
+
+/// How the wall clock shows hours.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TimeFormat {
+    /// Hours 00-23
+    #[default]
+    #[serde(rename = "24h")]
+    TwentyFourHour,
+    /// Hours 01-12
+    #[serde(rename = "12h")]
+    TwelveHour,
+}
+
+impl std::str::FromStr for TimeFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "24h" => Ok(TimeFormat::TwentyFourHour),
+            "12h" => Ok(TimeFormat::TwelveHour),
+            other => Err(format!(
+                "Unknown time format '{}' (expected 24h or 12h)",
+                other
+            )),
+        }
+    }
+}
+
+/// Parses an IANA timezone name such as `Europe/Berlin` or `UTC`.
+///
+/// # Returns
+/// * `Ok(None)` - Empty input or `local`, meaning the system timezone
+/// * `Ok(Some(Tz))` - The named timezone
+/// * `Err(String)` - The name is not in the timezone database
+pub fn parse_timezone(name: &str) -> Result<Option<Tz>, String> {
+    let name = name.trim();
+    if name.is_empty() || name.eq_ignore_ascii_case("local") {
+        return Ok(None);
+    }
+
+    name.parse::<Tz>()
+        .map(Some)
+        .map_err(|_| format!("Unknown timezone '{}' (e.g. Europe/Berlin or UTC)", name))
+}
+
+/// How the main window is shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WindowMode {
+    /// A regular window
+    #[default]
+    Normal,
+    /// Anchored to the screen, below other windows, out of the taskbar and
+    /// click-through
+    Widget,
+    /// Covering the whole screen
+    Fullscreen,
+}
+
+/// Where on the screen a widget is placed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Anchor {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    #[default]
+    BottomRight,
+}
+
+/// Screen edges a window is attached to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(not(any(feature = "layer-shell", feature = "x11")), allow(dead_code))]
+pub struct Edges {
+    pub left: bool,
+    pub right: bool,
+    pub top: bool,
+    pub bottom: bool,
+}
+
+#[cfg_attr(not(any(feature = "layer-shell", feature = "x11")), allow(dead_code))]
+impl Anchor {
+    /// Returns the screen edges this anchor attaches to; a window attached to
+    /// neither edge of an axis is centered on it.
+    pub fn edges(self) -> Edges {
+        let (left, right) = match self {
+            Anchor::TopLeft | Anchor::Left | Anchor::BottomLeft => (true, false),
+            Anchor::TopRight | Anchor::Right | Anchor::BottomRight => (false, true),
+            Anchor::Top | Anchor::Center | Anchor::Bottom => (false, false),
+        };
+        let (top, bottom) = match self {
+            Anchor::TopLeft | Anchor::Top | Anchor::TopRight => (true, false),
+            Anchor::BottomLeft | Anchor::Bottom | Anchor::BottomRight => (false, true),
+            Anchor::Left | Anchor::Center | Anchor::Right => (false, false),
+        };
+        Edges {
+            left,
+            right,
+            top,
+            bottom,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MarqueeConfig {
+    /// Message to scroll across the display at startup (empty for none)
+    pub message: String,
+    /// How long a message scrolls before the time returns, in seconds
+    pub duration_secs: u64,
+    /// Delay between scroll steps in milliseconds
+    pub step_ms: u64,
+}
+
+/// Timing of ambient mode, which shows the time on every clock's hands.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AmbientConfig {
+    /// How long each clock shows the time on its hands, in seconds
+    pub analog_secs: u64,
+    /// How long the clocks then form the digits, in seconds (0 for never)
+    pub digital_secs: u64,
+}
+
+impl Default for ThemeConfig {
+    fn default() -> Self {
+        ThemeConfig {
+            name: DEFAULT_THEME.to_string(),
+            follow_system: false,
+            light: "light".to_string(),
+            dark: "dark".to_string(),
+        }
+    }
 }
 
 impl Default for ColorConfig {
@@ -105,6 +972,15 @@ impl Default for ColorConfig {
             display_bg: "#ffffff0d".to_string(),          // 5% opacity
             display_border: "#ffffff1a".to_string(),      // 10% opacity
             separator_color: "#ff6b6b".to_string(),
+            hand_shadow: "#00000080".to_string(),
+            schedule: Vec::new(),
+            schedule_mode: ScheduleMode::Smooth,
+            hours: GroupColors::default(),
+            minutes: GroupColors::default(),
+            seconds: GroupColors::default(),
+            hour_hand: HandColors::default(),
+            minute_hand: HandColors::default(),
+            palette: BTreeMap::new(),
         }
     }
 }
@@ -113,7 +989,76 @@ impl Default for WindowConfig {
     fn default() -> Self {
         WindowConfig {
             transparent: false,
+            click_through: false,
             opacity: 1.0,
+            mode: WindowMode::Normal,
+            anchor: Anchor::BottomRight,
+            margin: 24,
+            custom_css: String::new(),
+            background: BackgroundConfig::default(),
+        }
+    }
+}
+
+impl Default for BackgroundConfig {
+    fn default() -> Self {
+        BackgroundConfig {
+            gradient: Gradient::None,
+            gradient_colors: Vec::new(),
+            gradient_angle: 180.0,
+            image: String::new(),
+            image_mode: ImageMode::Cover,
+        }
+    }
+}
+
+impl Default for FaceConfig {
+    fn default() -> Self {
+        FaceConfig {
+            style: FaceStyle::Circle,
+            ticks: TickMarks::None,
+            bevel: Bevel::None,
+            border_width: 1.0,
+            active: FaceOverrides::default(),
+            inactive: FaceOverrides::default(),
+        }
+    }
+}
+
+impl Default for EffectsConfig {
+    fn default() -> Self {
+        EffectsConfig {
+            glow_radius: 0.0,
+            glow_intensity: 0.5,
+            shadow: false,
+            shadow_offset_x: 1.0,
+            shadow_offset_y: 1.5,
+            shadow_blur: 1.5,
+            trail_length: 0,
+            trail_intensity: 0.5,
+        }
+    }
+}
+
+impl Default for HandStyle {
+    fn default() -> Self {
+        HandStyle {
+            length: 1.0,
+            width: 0.0,
+            cap: HandCap::Round,
+            taper: 0.0,
+            tail: 0.0,
+        }
+    }
+}
+
+impl Default for DisplayConfig {
+    fn default() -> Self {
+        DisplayConfig {
+            padding: 40,
+            border_radius: 20,
+            border_width: 1,
+            background: BackgroundConfig::default(),
         }
     }
 }
@@ -126,6 +1071,78 @@ impl Default for ClockConfig {
             clock_gap: 1,
             digit_gap: 8,
             animation_duration_ms: 300,
+            mode: DisplayMode::Clock,
+            format: TimeFormat::TwentyFourHour,
+            timezone: String::new(),
+        }
+    }
+}
+
+impl Default for AmbientConfig {
+    fn default() -> Self {
+        AmbientConfig {
+            analog_secs: 50,
+            digital_secs: 10,
+        }
+    }
+}
+
+impl Default for MarqueeConfig {
+    fn default() -> Self {
+        MarqueeConfig {
+            message: String::new(),
+            duration_secs: 20,
+            step_ms: 250,
+        }
+    }
+}
+
+/// Keyboard shortcuts for `app.*` actions, as GTK accelerators such as
+/// `F11`, `space` or `<Control>q`. An empty list removes an action's shortcuts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShortcutConfig {
+    pub toggle_fullscreen: Vec<String>,
+    pub toggle_running: Vec<String>,
+    pub reset: Vec<String>,
+    pub reload_config: Vec<String>,
+    pub zoom_in: Vec<String>,
+    pub zoom_out: Vec<String>,
+    pub preferences: Vec<String>,
+    pub cycle_theme: Vec<String>,
+    pub quit: Vec<String>,
+}
+
+impl ShortcutConfig {
+    /// Pairs each action name with its accelerators.
+    pub fn bindings(&self) -> [(&'static str, &[String]); 9] {
+        [
+            ("toggle-fullscreen", &self.toggle_fullscreen),
+            ("toggle-running", &self.toggle_running),
+            ("reset", &self.reset),
+            ("reload-config", &self.reload_config),
+            ("zoom-in", &self.zoom_in),
+            ("zoom-out", &self.zoom_out),
+            ("preferences", &self.preferences),
+            ("cycle-theme", &self.cycle_theme),
+            ("quit", &self.quit),
+        ]
+    }
+}
+
+impl Default for ShortcutConfig {
+    fn default() -> Self {
+        let keys = |keys: &[&str]| keys.iter().map(|k| k.to_string()).collect();
+        ShortcutConfig {
+            toggle_fullscreen: keys(&["F11"]),
+            toggle_running: keys(&["space"]),
+            reset: keys(&["BackSpace"]),
+            reload_config: keys(&["r"]),
+            zoom_in: keys(&["plus", "equal", "KP_Add"]),
+            zoom_out: keys(&["minus", "KP_Subtract"]),
+            preferences: keys(&["<Control>comma"]),
+            cycle_theme: keys(&["t"]),
+            quit: keys(&["q", "<Control>q"]),
         }
     }
 }
@@ -138,68 +1155,410 @@ impl Config {
     /// config. Later includes override earlier ones. Included files' own `include`
     /// keys are stripped (no recursive includes).
     ///
+    /// Runtime overrides are merged last and take precedence over both the main
+    /// file and its includes. They hold settings changed while the app is
+    /// running (e.g. over IPC), so those changes survive hot reloads.
+    ///
+    /// The theme selected by the merged `[theme]` section is then layered
+    /// underneath all of them, with the system accent colors on top of it when
+    /// following the system style. A user theme file is checked like an
+    /// include and added to the source files, so editing it reloads the config.
+    ///
+    /// Every file is checked on its own for syntax errors, wrongly typed values
+    /// and unknown keys, and the merged result is checked with `validate`.
+    /// Problems are reported with the file, line and column they come from.
+    ///
     /// # Arguments
     /// * `path` - Path to the TOML configuration file
+    /// * `overrides` - Table deep-merged over the loaded configuration
+    /// * `system` - Desktop appearance for `theme.follow_system`
     ///
     /// # Returns
-    /// * `Ok(ConfigLoadResult)` - Config and all source file paths
-    /// * `Err` - File not found, permission denied, or invalid TOML syntax
-    pub fn load(path: &PathBuf) -> Result<ConfigLoadResult, Box<dyn std::error::Error>> {
-        let contents = fs::read_to_string(path)?;
-        let mut table: Table = toml::from_str(&contents)?;
+    /// * `Ok(ConfigLoadResult)` - Config, all source file paths and any warnings
+    /// * `Err(ConfigError)` - Every problem found, at least one of them an error
+    pub fn load(
+        path: &PathBuf,
+        overrides: &Table,
+        system: &SystemStyle,
+    ) -> Result<ConfigLoadResult, ConfigError> {
+        let mut diagnostics = Vec::new();
+        let mut source_files = vec![path.clone()];
+
+        let contents = match fs::read_to_string(path) {
+            Ok(c) => c,
+            Err(e) => {
+                return Err(ConfigError {
+                    diagnostics: vec![Diagnostic::error_in(
+                        path,
+                        "",
+                        None,
+                        format!("could not read file: {}", e),
+                    )],
+                    source_files,
+                });
+            }
+        };
+
+        let Some(mut table) = check_file(path, &contents, &mut diagnostics) else {
+            return Err(ConfigError {
+                diagnostics,
+                source_files,
+            });
+        };
 
         let base_dir = path.parent().unwrap_or(Path::new("."));
-        let mut source_files = vec![path.clone()];
+        // Files in order of increasing precedence, for locating problems later
+        let mut files = vec![(path.clone(), contents)];
 
         // Extract and remove the `include` array before deserialization
-        if let Some(include_val) = table.remove("include") {
-            if let Some(includes) = include_val.as_array() {
-                for item in includes {
-                    if let Some(include_str) = item.as_str() {
-                        let include_path = resolve_include_path(base_dir, include_str);
-                        let canonical = match fs::canonicalize(&include_path) {
-                            Ok(p) => p,
-                            Err(e) => {
-                                eprintln!(
-                                    "Warning: Could not resolve include path {:?}: {}",
-                                    include_path, e
-                                );
-                                continue;
-                            }
-                        };
-
-                        match fs::read_to_string(&canonical) {
-                            Ok(inc_contents) => match toml::from_str::<Table>(&inc_contents) {
-                                Ok(mut inc_table) => {
-                                    // Strip any nested include keys (no recursive includes)
-                                    inc_table.remove("include");
-                                    deep_merge_toml(&mut table, inc_table);
-                                    source_files.push(canonical);
-                                }
-                                Err(e) => {
-                                    eprintln!(
-                                        "Warning: Failed to parse include file {:?}: {}",
-                                        canonical, e
-                                    );
-                                }
-                            },
-                            Err(e) => {
-                                eprintln!(
-                                    "Warning: Could not read include file {:?}: {}",
-                                    canonical, e
-                                );
-                            }
+        if let Some(include_val) = table.remove("include")
+            && let Some(includes) = include_val.as_array()
+        {
+            let include_span = key_span(&files[0].1, "include");
+            for item in includes {
+                if let Some(include_str) = item.as_str() {
+                    let include_path = resolve_include_path(base_dir, include_str);
+                    let canonical = match fs::canonicalize(&include_path) {
+                        Ok(p) => p,
+                        Err(e) => {
+                            diagnostics.push(Diagnostic::error_in(
+                                path,
+                                &files[0].1,
+                                include_span.clone(),
+                                format!("could not resolve include {:?}: {}", include_path, e),
+                            ));
+                            continue;
                         }
+                    };
+                    source_files.push(canonical.clone());
+
+                    let inc_contents = match fs::read_to_string(&canonical) {
+                        Ok(c) => c,
+                        Err(e) => {
+                            diagnostics.push(Diagnostic::error_in(
+                                &canonical,
+                                "",
+                                None,
+                                format!("could not read include file: {}", e),
+                            ));
+                            continue;
+                        }
+                    };
+
+                    if let Some(mut inc_table) =
+                        check_file(&canonical, &inc_contents, &mut diagnostics)
+                    {
+                        // Strip any nested include keys (no recursive includes)
+                        inc_table.remove("include");
+                        deep_merge_toml(&mut table, inc_table);
+                        files.push((canonical, inc_contents));
                     }
                 }
             }
         }
 
-        let config: Config = toml::Value::Table(table).try_into()?;
-        Ok(ConfigLoadResult {
-            config,
-            source_files,
-        })
+        deep_merge_toml(&mut table, overrides.clone());
+
+        let mut theme_file = None;
+        let mut theme_table = Table::new();
+        let theme_config = ThemeConfig::from_table(&table);
+        let (theme_key, theme_name) = theme_config.selected(system);
+        let themes_dir = theme::themes_dir(base_dir);
+        match theme::find(theme_name, &themes_dir) {
+            Some(ThemeSource::Builtin(contents)) => {
+                theme_table = toml::from_str(contents).unwrap_or_default();
+            }
+            Some(ThemeSource::File(theme_path)) => {
+                source_files.push(theme_path.clone());
+                match fs::read_to_string(&theme_path) {
+                    Ok(theme_contents) => {
+                        if let Some(mut file_table) =
+                            check_file(&theme_path, &theme_contents, &mut diagnostics)
+                        {
+                            // A theme can't include files or pick another theme
+                            file_table.remove("include");
+                            file_table.remove("theme");
+                            theme_table = file_table;
+                            files.insert(0, (theme_path.clone(), theme_contents));
+                        }
+                    }
+                    Err(e) => diagnostics.push(Diagnostic::error_in(
+                        &theme_path,
+                        "",
+                        None,
+                        format!("could not read theme file: {}", e),
+                    )),
+                }
+                theme_file = Some(theme_path);
+            }
+            None => diagnostics.push(locate_problem(
+                &files,
+                overrides,
+                theme_key,
+                &format!(
+                    "unknown theme '{}' (available: {})",
+                    theme_name,
+                    theme::names(&themes_dir).join(", ")
+                ),
+            )),
+        }
+        if theme_config.follow_system
+            && let Some(accent) = system.accent_colors()
+        {
+            deep_merge_toml(&mut theme_table, accent);
+        }
+        table = layer_under(theme_table, table);
+
+        let mut config = match toml::Value::Table(table).try_into::<Config>() {
+            Ok(config) => Some(config),
+            Err(e) => {
+                // Files were type-checked individually, so this is an override
+                diagnostics.push(Diagnostic::error(format!("override: {}", e.message())));
+                None
+            }
+        };
+
+        let mut custom_css = None;
+        let mut images = Vec::new();
+        if let Some(config) = &mut config {
+            for (key, message) in config.validate() {
+                diagnostics.push(locate_problem(&files, overrides, &key, &message));
+            }
+            // References are resolved across all files, once merged
+            config.colors.resolve();
+
+            // The custom stylesheet is watched like an include
+            if !config.window.custom_css.is_empty() {
+                let css_path = resolve_include_path(base_dir, &config.window.custom_css);
+                if !css_path.exists() {
+                    let mut problem =
+                        locate_problem(&files, overrides, "window.custom_css", "file not found");
+                    problem.severity = Severity::Warning;
+                    diagnostics.push(problem);
+                }
+                source_files.push(css_path.clone());
+                custom_css = Some(css_path);
+            }
+
+            // Background images are made absolute for the stylesheet, and
+            // watched like it so a replaced image shows up
+            for (key, background) in [
+                ("window.background.image", &mut config.window.background),
+                ("display.background.image", &mut config.display.background),
+            ] {
+                if background.image.is_empty() {
+                    continue;
+                }
+                let image_path = resolve_include_path(base_dir, &background.image);
+                if !image_path.exists() {
+                    let mut problem = locate_problem(&files, overrides, key, "file not found");
+                    problem.severity = Severity::Warning;
+                    diagnostics.push(problem);
+                }
+                background.image = image_path.to_string_lossy().into_owned();
+                source_files.push(image_path.clone());
+                images.push(image_path);
+            }
+        }
+
+        match config {
+            Some(config) if !diagnostics.iter().any(Diagnostic::is_error) => Ok(ConfigLoadResult {
+                config,
+                source_files,
+                diagnostics,
+                custom_css,
+                theme_file,
+                images,
+            }),
+            _ => Err(ConfigError {
+                diagnostics,
+                source_files,
+            }),
+        }
+    }
+
+    /// Checks the merged configuration for values outside their valid range.
+    ///
+    /// # Returns
+    /// A list of `(key, problem)` pairs, where `key` is dotted (e.g. `window.opacity`)
+    pub fn validate(&self) -> Vec<(String, String)> {
+        let mut problems = Vec::new();
+        let mut check = |key: &str, ok: bool, message: String| {
+            if !ok {
+                problems.push((key.to_string(), message));
+            }
+        };
+
+        // Colors are checked as written, each problem where it is written
+        for (key, color) in self.colors.settings() {
+            if let Err(e) = self.colors.check(&key, color) {
+                check(&key, false, e);
+            }
+        }
+        for (i, point) in self.colors.schedule.iter().enumerate() {
+            if let Err(e) = parse_time_of_day(&point.time) {
+                check(&format!("colors.schedule.{}.time", i), false, e);
+            }
+            for key in point.colors.keys() {
+                if self.colors.get(key).is_none() {
+                    let message = format!("unknown color '{}'", key);
+                    check(&format!("colors.schedule.{}.{}", i, key), false, message);
+                }
+            }
+        }
+
+        let opacity = self.window.opacity;
+        check(
+            "window.opacity",
+            (0.0..=1.0).contains(&opacity),
+            format!("must be between 0.0 and 1.0 (got {})", opacity),
+        );
+        check(
+            "window.margin",
+            self.window.margin >= 0,
+            format!("must not be negative (got {})", self.window.margin),
+        );
+
+        let display = &self.display;
+        for (key, value) in [
+            ("display.padding", display.padding),
+            ("display.border_radius", display.border_radius),
+            ("display.border_width", display.border_width),
+        ] {
+            check(
+                key,
+                value >= 0,
+                format!("must not be negative (got {})", value),
+            );
+        }
+        let face = &self.face;
+        for (key, width) in [
+            ("face.border_width", Some(face.border_width)),
+            ("face.active.border_width", face.active.border_width),
+            ("face.inactive.border_width", face.inactive.border_width),
+        ] {
+            if let Some(width) = width {
+                check(
+                    key,
+                    width >= 0.0,
+                    format!("must not be negative (got {})", width),
+                );
+            }
+        }
+        let effects = &self.effects;
+        for (key, value) in [
+            ("effects.glow_radius", effects.glow_radius),
+            ("effects.shadow_blur", effects.shadow_blur),
+        ] {
+            check(
+                key,
+                value >= 0.0,
+                format!("must not be negative (got {})", value),
+            );
+        }
+        for (key, value) in [
+            ("effects.glow_intensity", effects.glow_intensity),
+            ("effects.trail_intensity", effects.trail_intensity),
+        ] {
+            check(
+                key,
+                (0.0..=1.0).contains(&value),
+                format!("must be between 0.0 and 1.0 (got {})", value),
+            );
+        }
+        check(
+            "effects.trail_length",
+            effects.trail_length <= MAX_TRAIL_LENGTH,
+            format!(
+                "must be at most {} (got {})",
+                MAX_TRAIL_LENGTH, effects.trail_length
+            ),
+        );
+        for (name, hand) in [("hour", &self.hands.hour), ("minute", &self.hands.minute)] {
+            check(
+                &format!("hands.{}.length", name),
+                hand.length > 0.0,
+                format!("must be positive (got {})", hand.length),
+            );
+            check(
+                &format!("hands.{}.width", name),
+                hand.width >= 0.0,
+                format!("must not be negative (got {})", hand.width),
+            );
+            check(
+                &format!("hands.{}.taper", name),
+                (0.0..=1.0).contains(&hand.taper),
+                format!("must be between 0.0 and 1.0 (got {})", hand.taper),
+            );
+            check(
+                &format!("hands.{}.tail", name),
+                hand.tail >= 0.0,
+                format!("must not be negative (got {})", hand.tail),
+            );
+        }
+        for (section, background) in [
+            ("window", &self.window.background),
+            ("display", &display.background),
+        ] {
+            let key = format!("{}.background.gradient_colors", section);
+            check(
+                &key,
+                background.gradient == Gradient::None || background.gradient_colors.len() >= 2,
+                "a gradient needs at least 2 colors".to_string(),
+            );
+            for color in &background.gradient_colors {
+                if let Err(e) = self.colors.eval(color) {
+                    check(&key, false, e);
+                }
+            }
+        }
+
+        let clock = &self.clock;
+        check(
+            "clock.size",
+            clock.size > 0,
+            format!("must be greater than 0 (got {})", clock.size),
+        );
+        check(
+            "clock.stroke_width",
+            clock.stroke_width > 0.0,
+            format!("must be greater than 0 (got {})", clock.stroke_width),
+        );
+        check(
+            "clock.clock_gap",
+            clock.clock_gap >= 0,
+            format!("must not be negative (got {})", clock.clock_gap),
+        );
+        check(
+            "clock.digit_gap",
+            clock.digit_gap >= 0,
+            format!("must not be negative (got {})", clock.digit_gap),
+        );
+        if let Err(e) = parse_timezone(&clock.timezone) {
+            check("clock.timezone", false, e);
+        }
+
+        check(
+            "marquee.step_ms",
+            self.marquee.step_ms > 0,
+            "must be greater than 0".to_string(),
+        );
+
+        problems
+    }
+
+    /// Returns the default configuration as TOML, for `--print-default-config`.
+    ///
+    /// The `COLOR_KEYS` are left out: a config file setting them would take
+    /// precedence over every theme, so they are left to `[theme]`.
+    pub fn default_toml() -> Result<String, toml::ser::Error> {
+        let mut table = Table::try_from(Config::default())?;
+        if let Some(toml::Value::Table(colors)) = table.get_mut("colors") {
+            for key in COLOR_KEYS {
+                colors.remove(key);
+            }
+        }
+        toml::to_string_pretty(&table)
     }
 
     /// Returns the platform-specific default path for the config file.
@@ -218,227 +1577,202 @@ impl Config {
         path
     }
 
-    /// Loads config from the default path, falling back to defaults if unavailable.
+    /// Loads config from a source, falling back to defaults if unavailable.
     ///
     /// Returns a `ConfigLoadResult` containing the config and all source file paths.
-    /// If loading fails, returns default config with just the default path as source.
-    pub fn load_or_default() -> ConfigLoadResult {
-        let path = Self::default_path();
-        Self::load(&path).unwrap_or_else(|_| {
-            eprintln!("Could not load config from {:?}, using defaults", path);
+    /// If loading fails, returns default config with the files read so far as
+    /// sources, and the problems in `diagnostics`. A missing config file is not
+    /// reported as a problem. The source's overrides are applied in both cases.
+    ///
+    /// # Arguments
+    /// * `source` - Config file path (or the default path) and overrides
+    pub fn load_or_default(source: &ConfigSource) -> ConfigLoadResult {
+        let path = source.path();
+        let themes_dir = theme::themes_dir(path.parent().unwrap_or(Path::new(".")));
+        Self::load(&path, &source.overrides, &source.system).unwrap_or_else(|e| {
+            let diagnostics = if path.exists() {
+                e.diagnostics
+            } else {
+                eprintln!("Could not load config from {:?}, using defaults", path);
+                Vec::new()
+            };
             ConfigLoadResult {
-                config: Self::default(),
-                source_files: vec![path],
+                config: Self::default_with_overrides(
+                    &source.overrides,
+                    &themes_dir,
+                    &source.system,
+                ),
+                source_files: e.source_files,
+                diagnostics,
+                custom_css: None,
+                theme_file: None,
+                images: Vec::new(),
             }
         })
     }
-}
 
-/// Resolves an include path relative to a base directory.
-///
-/// If the include path is absolute, it is returned as-is.
-/// If relative, it is joined to the base directory.
-pub fn resolve_include_path(base_dir: &Path, include: &str) -> PathBuf {
-    let path = Path::new(include);
-    if path.is_absolute() {
-        path.to_path_buf()
-    } else {
-        base_dir.join(path)
-    }
-}
+    /// Returns the default config with runtime overrides merged on top, and
+    /// the theme they select, if any, underneath.
+    ///
+    /// Falls back to plain defaults if the overrides don't fit the schema.
+    ///
+    /// # Arguments
+    /// * `overrides` - Table deep-merged over the defaults
+    /// * `themes_dir` - User themes directory (see `theme::themes_dir`)
+    /// * `system` - Desktop appearance for `theme.follow_system`
+    fn default_with_overrides(
+        overrides: &Table,
+        themes_dir: &Path,
+        system: &SystemStyle,
+    ) -> Config {
+        let Ok(toml::Value::Table(mut table)) = toml::Value::try_from(Self::default()) else {
+            return Self::default();
+        };
+        deep_merge_toml(&mut table, overrides.clone());
 
-/// Deep-merges an overlay TOML table into a base table.
-///
-/// For nested tables, merges recursively. For all other value types,
-/// the overlay value replaces the base value.
-pub fn deep_merge_toml(base: &mut Table, overlay: Table) {
-    for (key, overlay_val) in overlay {
-        match (base.get_mut(&key), overlay_val.clone()) {
-            (Some(toml::Value::Table(base_table)), toml::Value::Table(overlay_table)) => {
-                deep_merge_toml(base_table, overlay_table);
-            }
-            _ => {
-                base.insert(key, overlay_val);
-            }
+        let theme_config = ThemeConfig::from_table(&table);
+        let mut theme_table: Table = match theme::find(theme_config.selected(system).1, themes_dir)
+        {
+            Some(ThemeSource::Builtin(contents)) => toml::from_str(contents).unwrap_or_default(),
+            Some(ThemeSource::File(path)) => fs::read_to_string(path)
+                .ok()
+                .and_then(|contents| toml::from_str(&contents).ok())
+                .unwrap_or_default(),
+            None => Table::new(),
+        };
+        theme_table.remove("include");
+        theme_table.remove("theme");
+        if theme_config.follow_system
+            && let Some(accent) = system.accent_colors()
+        {
+            deep_merge_toml(&mut theme_table, accent);
         }
+
+        // Defaults set every key, so the theme goes over them and the
+        // overrides go back on top
+        deep_merge_toml(&mut table, theme_table);
+        deep_merge_toml(&mut table, overrides.clone());
+        let mut config: Config = toml::Value::Table(table).try_into().unwrap_or_default();
+        config.colors.resolve();
+        config
     }
 }
 
-/// Parses a hex color string into Cairo-compatible RGBA values.
-///
-/// Converts hex color strings (with or without alpha) into normalized RGBA
-/// values in the range 0.0-1.0, suitable for use with Cairo rendering.
-///
-/// # Supported Formats
-/// - `#RRGGBB` - 6 hex digits (RGB), alpha defaults to 1.0 (fully opaque)
-/// - `#RRGGBBAA` - 8 hex digits (RGBA), alpha specified
+/// Parses one config file and checks it on its own.
 ///
-/// Leading `#` is optional. Invalid hex digits default to 0.
-/// Unsupported lengths default to black: `(0.0, 0.0, 0.0, 1.0)`.
-///
-/// # Arguments
-/// * `hex` - Hex color string (e.g., "#ff6b6b" or "#ff6b6b26")
+/// Syntax errors and wrongly typed values are errors; unknown keys are
+/// warnings. Each is pushed to `diagnostics` with its line and column.
 ///
 /// # Returns
-/// Tuple of `(red, green, blue, alpha)` where each value is 0.0-1.0
-///
-/// # Examples
-/// ```
-/// # use chronomatrix::config::parse_hex_color;
-/// // RGB color
-/// let red = parse_hex_color("#ff0000");
-/// assert_eq!(red, (1.0, 0.0, 0.0, 1.0));
-///
-/// // RGBA with 15% opacity (0x26 = 38 / 255 ≈ 0.149)
-/// let semi_transparent = parse_hex_color("#ff6b6b26");
-/// assert_eq!(semi_transparent.3, 38.0 / 255.0);
-/// ```
-pub fn parse_hex_color(hex: &str) -> (f64, f64, f64, f64) {
-    let hex = hex.trim_start_matches('#');
-
-    let (r, g, b, a) = match hex.len() {
-        6 => {
-            let r = u8::from_str_radix(&hex[0..2], 16).unwrap_or_else(|_| {
-                eprintln!(
-                    "Warning: Invalid red component in hex color '{}', using 0",
-                    hex
-                );
-                0
-            });
-            let g = u8::from_str_radix(&hex[2..4], 16).unwrap_or_else(|_| {
-                eprintln!(
-                    "Warning: Invalid green component in hex color '{}', using 0",
-                    hex
-                );
-                0
-            });
-            let b = u8::from_str_radix(&hex[4..6], 16).unwrap_or_else(|_| {
-                eprintln!(
-                    "Warning: Invalid blue component in hex color '{}', using 0",
-                    hex
-                );
-                0
-            });
-            (r, g, b, 255)
-        }
-        8 => {
-            let r = u8::from_str_radix(&hex[0..2], 16).unwrap_or_else(|_| {
-                eprintln!(
-                    "Warning: Invalid red component in hex color '{}', using 0",
-                    hex
-                );
-                0
-            });
-            let g = u8::from_str_radix(&hex[2..4], 16).unwrap_or_else(|_| {
-                eprintln!(
-                    "Warning: Invalid green component in hex color '{}', using 0",
-                    hex
-                );
-                0
-            });
-            let b = u8::from_str_radix(&hex[4..6], 16).unwrap_or_else(|_| {
-                eprintln!(
-                    "Warning: Invalid blue component in hex color '{}', using 0",
-                    hex
-                );
-                0
-            });
-            let a = u8::from_str_radix(&hex[6..8], 16).unwrap_or_else(|_| {
-                eprintln!(
-                    "Warning: Invalid alpha component in hex color '{}', using 255",
-                    hex
-                );
-                255
-            });
-            (r, g, b, a)
-        }
-        _ => {
-            eprintln!(
-                "Warning: Invalid hex color format '{}' (expected 6 or 8 characters), using black",
-                hex
-            );
-            (0, 0, 0, 255)
+/// The file's table, or `None` if it has errors
+fn check_file(path: &Path, contents: &str, diagnostics: &mut Vec<Diagnostic>) -> Option<Table> {
+    let table: Table = match toml::from_str(contents) {
+        Ok(t) => t,
+        Err(e) => {
+            diagnostics.push(Diagnostic::error_in(path, contents, e.span(), e.message()));
+            return None;
         }
     };
 
-    (
-        r as f64 / 255.0,
-        g as f64 / 255.0,
-        b as f64 / 255.0,
-        a as f64 / 255.0,
-    )
+    // Every field has a default, so each file deserializes on its own
+    if let Err(e) = toml::from_str::<Config>(contents) {
+        diagnostics.push(Diagnostic::error_in(path, contents, e.span(), e.message()));
+        return None;
+    }
+
+    if let Ok(toml::Value::Table(schema)) = toml::Value::try_from(Config::default()) {
+        for key in unknown_keys(&table, &schema, "") {
+            if key == "include" {
+                continue;
+            }
+            diagnostics.push(Diagnostic::warning_in(
+                path,
+                contents,
+                key_span(contents, &key),
+                format!("unknown key '{}'", key),
+            ));
+        }
+    }
+
+    Some(table)
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Attributes a problem with a merged value to where it was set.
+///
+/// Looks in the overrides first, then the files from highest to lowest
+/// precedence; a value none of them set is a bad default.
+fn locate_problem(
+    files: &[(PathBuf, String)],
+    overrides: &Table,
+    key: &str,
+    message: &str,
+) -> Diagnostic {
+    let text = format!("{}: {}", key, message);
 
-    #[test]
-    fn test_parse_hex_color_rgb() {
-        // Test standard RGB format
-        let (r, g, b, a) = parse_hex_color("#ff0000");
-        assert_eq!(r, 1.0);
-        assert_eq!(g, 0.0);
-        assert_eq!(b, 0.0);
-        assert_eq!(a, 1.0);
+    if table_has_key(overrides, key) {
+        return Diagnostic::error(format!("override: {}", text));
     }
 
-    #[test]
-    fn test_parse_hex_color_rgba() {
-        // Test RGBA format with transparency
-        let (r, g, b, a) = parse_hex_color("#ff6b6b26");
-        assert_eq!(r, 1.0);
-        assert!((g - 0.4196).abs() < 0.01); // 0x6b = 107 / 255 ≈ 0.4196
-        assert!((b - 0.4196).abs() < 0.01);
-        assert!((a - 0.1490).abs() < 0.01); // 0x26 = 38 / 255 ≈ 0.149
-    }
+    files
+        .iter()
+        .rev()
+        .find_map(|(path, contents)| {
+            key_span(contents, key)
+                .map(|span| Diagnostic::error_in(path, contents, Some(span), &text))
+        })
+        .unwrap_or_else(|| Diagnostic::error(text))
+}
 
-    #[test]
-    fn test_parse_hex_color_no_hash() {
-        // Test without leading #
-        let (r, g, b, a) = parse_hex_color("00ff00");
-        assert_eq!(r, 0.0);
-        assert_eq!(g, 1.0);
-        assert_eq!(b, 0.0);
-        assert_eq!(a, 1.0);
+/// Returns whether a table sets a dotted key such as `colors.clock_bg`.
+fn table_has_key(table: &Table, key: &str) -> bool {
+    match key.split_once('.') {
+        Some((section, rest)) => {
+            matches!(table.get(section), Some(toml::Value::Table(t)) if table_has_key(t, rest))
+        }
+        None => table.contains_key(key),
     }
+}
 
-    #[test]
-    fn test_parse_hex_color_black() {
-        let (r, g, b, a) = parse_hex_color("#000000");
-        assert_eq!(r, 0.0);
-        assert_eq!(g, 0.0);
-        assert_eq!(b, 0.0);
-        assert_eq!(a, 1.0);
-    }
+/// Deep-merges `table` over `base` and returns the result, so `base` fills
+/// in only what `table` leaves unset.
+fn layer_under(mut base: Table, table: Table) -> Table {
+    deep_merge_toml(&mut base, table);
+    base
+}
 
-    #[test]
-    fn test_parse_hex_color_white() {
-        let (r, g, b, a) = parse_hex_color("#ffffff");
-        assert_eq!(r, 1.0);
-        assert_eq!(g, 1.0);
-        assert_eq!(b, 1.0);
-        assert_eq!(a, 1.0);
+/// Resolves an include path relative to a base directory.
+///
+/// If the include path is absolute, it is returned as-is.
+/// If relative, it is joined to the base directory.
+pub fn resolve_include_path(base_dir: &Path, include: &str) -> PathBuf {
+    let path = Path::new(include);
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        base_dir.join(path)
     }
+}
 
-    #[test]
-    fn test_parse_hex_color_fully_transparent() {
-        let (r, g, b, a) = parse_hex_color("#ff000000");
-        assert_eq!(r, 1.0);
-        assert_eq!(g, 0.0);
-        assert_eq!(b, 0.0);
-        assert_eq!(a, 0.0);
+/// Deep-merges an overlay TOML table into a base table.
+///
+/// For nested tables, merges recursively. For all other value types,
+/// the overlay value replaces the base value.
+pub fn deep_merge_toml(base: &mut Table, overlay: Table) {
+    for (key, overlay_val) in overlay {
+        match (base.get_mut(&key), overlay_val.clone()) {
+            (Some(toml::Value::Table(base_table)), toml::Value::Table(overlay_table)) => {
+                deep_merge_toml(base_table, overlay_table);
+            }
+            _ => {
+                base.insert(key, overlay_val);
+            }
+        }
     }
+}
 
-    #[test]
-    fn test_parse_hex_color_invalid_length() {
-        // Invalid length should default to black
-        let (r, g, b, a) = parse_hex_color("#fff");
-        assert_eq!(r, 0.0);
-        assert_eq!(g, 0.0);
-        assert_eq!(b, 0.0);
-        assert_eq!(a, 1.0);
-    }
+#[cfg(test)]
+mod tests {
+    use super::*;
 
     #[test]
     fn test_config_default() {
@@ -565,11 +1899,673 @@ clock_hand_color = "#112233"
         )
         .unwrap();
 
-        let result = Config::load(&main_config).unwrap();
+        let result = Config::load(&main_config, &Table::new(), &SystemStyle::default()).unwrap();
         assert_eq!(result.config.colors.window_background, "#abcdef");
         assert_eq!(result.config.colors.clock_hand_color, "#112233");
         assert_eq!(result.source_files.len(), 2);
 
         let _ = fs::remove_dir_all(&dir);
     }
+
+    #[test]
+    fn test_default_with_overrides() {
+        let overrides: Table = toml::from_str(
+            r##"
+            [colors]
+            clock_hand_color = "#00ff00"
+            "##,
+        )
+        .unwrap();
+        let config = Config::default_with_overrides(
+            &overrides,
+            Path::new("/nonexistent"),
+            &SystemStyle::default(),
+        );
+        assert_eq!(config.colors.clock_hand_color, "#00ff00");
+        assert_eq!(config.colors.window_background, "#0f0c29");
+    }
+
+    #[test]
+    fn test_load_with_overrides() {
+        use std::io::Write;
+        let dir = std::env::temp_dir().join("chronomatrix_test_overrides");
+        let _ = fs::create_dir_all(&dir);
+
+        let main_config = dir.join("config.toml");
+        let mut f = fs::File::create(&main_config).unwrap();
+        writeln!(
+            f,
+            r##"
+[colors]
+clock_hand_color = "#112233"
+separator_color = "#445566"
+"##
+        )
+        .unwrap();
+
+        let overrides: Table = toml::from_str(
+            r##"
+            [colors]
+            clock_hand_color = "#abcdef"
+            "##,
+        )
+        .unwrap();
+
+        let result = Config::load(&main_config, &overrides, &SystemStyle::default()).unwrap();
+        assert_eq!(result.config.colors.clock_hand_color, "#abcdef");
+        assert_eq!(result.config.colors.separator_color, "#445566");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_config_source_set() {
+        let mut source = ConfigSource::default();
+        source.set("clock", "format", toml::Value::String("12h".to_string()));
+        source.set(
+            "clock",
+            "mode",
+            toml::Value::String("stopwatch".to_string()),
+        );
+        source.set(
+            "window",
+            "mode",
+            toml::Value::String("fullscreen".to_string()),
+        );
+
+        source.set("theme", "name", toml::Value::String("light".to_string()));
+        source.set(
+            "colors",
+            "clock_bg",
+            toml::Value::String("#010203".to_string()),
+        );
+
+        let config = Config::default_with_overrides(
+            &source.overrides,
+            Path::new("/nonexistent"),
+            &source.system,
+        );
+        assert_eq!(config.clock.format, TimeFormat::TwelveHour);
+        assert_eq!(config.clock.mode, DisplayMode::Stopwatch);
+        assert_eq!(config.window.mode, WindowMode::Fullscreen);
+        assert_eq!(config.clock.size, 40);
+        assert_eq!(config.colors.window_background, "#f5f5f5");
+        assert_eq!(config.colors.clock_bg, "#010203");
+    }
+
+    #[test]
+    fn test_time_format_from_str() {
+        assert_eq!("24h".parse::<TimeFormat>(), Ok(TimeFormat::TwentyFourHour));
+        assert_eq!("12H".parse::<TimeFormat>(), Ok(TimeFormat::TwelveHour));
+        assert!("13h".parse::<TimeFormat>().is_err());
+    }
+
+    #[test]
+    fn test_validate_ranges() {
+        assert!(Config::default().validate().is_empty());
+
+        let mut config = Config::default();
+        config.window.opacity = 1.5;
+        config.clock.size = 0;
+        config.clock.timezone = "Nowhere/Special".to_string();
+        config.colors.clock_bg = "#12345".to_string();
+
+        let keys: Vec<String> = config.validate().into_iter().map(|(k, _)| k).collect();
+        assert_eq!(
+            keys,
+            vec![
+                "colors.clock_bg",
+                "window.opacity",
+                "clock.size",
+                "clock.timezone"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_load_reports_locations() {
+        let dir = std::env::temp_dir().join("chronomatrix_test_validation");
+        let _ = fs::create_dir_all(&dir);
+        let main_config = dir.join("config.toml");
+        let theme_file = dir.join("theme.toml");
+
+        fs::write(
+            &main_config,
+            "include = [\"theme.toml\"]\n\n[window]\nopacity = 1.5\n",
+        )
+        .unwrap();
+        fs::write(
+            &theme_file,
+            "[colors]\nclock_bg = \"#12\"\nhand = \"#fff\"\n",
+        )
+        .unwrap();
+
+        let err = Config::load(&main_config, &Table::new(), &SystemStyle::default()).unwrap_err();
+        let messages: Vec<String> = err.diagnostics.iter().map(|d| d.to_string()).collect();
+        let canonical_theme = fs::canonicalize(&theme_file).unwrap();
+
+        assert_eq!(messages.len(), 3);
+        assert_eq!(
+            messages[0],
+            format!(
+                "{}:3:1: warning: unknown key 'colors.hand'",
+                canonical_theme.display()
+            )
+        );
+        assert!(messages[1].starts_with(&format!(
+            "{}:2:1: error: colors.clock_bg: invalid color",
+            canonical_theme.display()
+        )));
+        assert!(messages[2].starts_with(&format!(
+            "{}:4:1: error: window.opacity: must be between",
+            main_config.display()
+        )));
+        assert_eq!(err.source_files.len(), 2);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_load_reports_syntax_and_type_errors() {
+        let dir = std::env::temp_dir().join("chronomatrix_test_syntax");
+        let _ = fs::create_dir_all(&dir);
+        let main_config = dir.join("config.toml");
+
+        fs::write(&main_config, "[clock]\nsize = \"big\"\n").unwrap();
+        let err = Config::load(&main_config, &Table::new(), &SystemStyle::default()).unwrap_err();
+        assert_eq!(err.diagnostics.len(), 1);
+        assert_eq!(err.diagnostics[0].position, Some((2, 8)));
+
+        fs::write(&main_config, "[clock\nsize = 4\n").unwrap();
+        let err = Config::load(&main_config, &Table::new(), &SystemStyle::default()).unwrap_err();
+        assert_eq!(err.diagnostics[0].position.map(|(line, _)| line), Some(1));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_load_custom_css() {
+        let dir = std::env::temp_dir().join("chronomatrix_test_custom_css");
+        let _ = fs::create_dir_all(&dir);
+        let main_config = dir.join("config.toml");
+        let css_file = dir.join("style.css");
+
+        fs::write(&main_config, "[window]\ncustom_css = \"style.css\"\n").unwrap();
+        let result = Config::load(&main_config, &Table::new(), &SystemStyle::default()).unwrap();
+        assert_eq!(result.custom_css, Some(css_file.clone()));
+        assert!(result.source_files.contains(&css_file));
+        // A missing stylesheet is only a warning
+        assert_eq!(result.diagnostics.len(), 1);
+        assert_eq!(result.diagnostics[0].severity, Severity::Warning);
+        assert_eq!(result.diagnostics[0].position, Some((2, 1)));
+
+        fs::write(&css_file, "window {}\n").unwrap();
+        let result = Config::load(&main_config, &Table::new(), &SystemStyle::default()).unwrap();
+        assert!(result.diagnostics.is_empty());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_window_mode_and_anchor() {
+        let config: Config =
+            toml::from_str("[window]\nmode = \"widget\"\nanchor = \"top-left\"\n").unwrap();
+        assert_eq!(config.window.mode, WindowMode::Widget);
+        assert_eq!(
+            config.window.anchor.edges(),
+            Edges {
+                left: true,
+                right: false,
+                top: true,
+                bottom: false,
+            }
+        );
+
+        let center = Anchor::Center.edges();
+        assert!(!center.left && !center.right && !center.top && !center.bottom);
+        assert!(toml::from_str::<Config>("[window]\nmode = \"floating\"\n").is_err());
+    }
+
+    #[test]
+    fn test_partial_shortcuts_keep_defaults() {
+        let config: Config = toml::from_str("[shortcuts]\nquit = []\n").unwrap();
+        assert!(config.shortcuts.quit.is_empty());
+        assert_eq!(config.shortcuts.toggle_fullscreen, vec!["F11"]);
+        assert!(
+            config
+                .shortcuts
+                .bindings()
+                .iter()
+                .any(|(action, _)| *action == "toggle-running")
+        );
+    }
+
+    #[test]
+    fn test_load_theme() {
+        let dir = std::env::temp_dir().join("chronomatrix_test_theme");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("themes")).unwrap();
+        let main_config = dir.join("config.toml");
+
+        // The config's own colors win over the theme's
+        fs::write(
+            &main_config,
+            "[theme]\nname = \"nord\"\n\n[colors]\nclock_bg = \"#010203\"\n",
+        )
+        .unwrap();
+        let result = Config::load(&main_config, &Table::new(), &SystemStyle::default()).unwrap();
+        assert_eq!(result.config.colors.window_background, "#2e3440");
+        assert_eq!(result.config.colors.clock_bg, "#010203");
+        assert_eq!(result.theme_file, None);
+
+        // A user theme replaces the built-in one and is watched
+        let user_theme = dir.join("themes").join("nord.toml");
+        fs::write(&user_theme, "[colors]\nwindow_background = \"#123456\"\n").unwrap();
+        let result = Config::load(&main_config, &Table::new(), &SystemStyle::default()).unwrap();
+        assert_eq!(result.config.colors.window_background, "#123456");
+        assert_eq!(result.config.colors.clock_hand_color, "#ff6b6b");
+        assert_eq!(result.theme_file, Some(user_theme.clone()));
+        assert!(result.source_files.contains(&user_theme));
+
+        // Overrides switch themes at runtime
+        let mut source = ConfigSource::default();
+        source.set("theme", "name", toml::Value::String("gruvbox".to_string()));
+        let result = Config::load(&main_config, &source.overrides, &source.system).unwrap();
+        assert_eq!(result.config.colors.window_background, "#282828");
+
+        // Following the system picks the light or dark theme and its accent,
+        // still beneath the config's own colors
+        fs::write(
+            &main_config,
+            "[theme]\nfollow_system = true\ndark = \"gruvbox\"\n\n[colors]\nclock_bg = \"#010203\"\n",
+        )
+        .unwrap();
+        let mut system = SystemStyle::default();
+        let result = Config::load(&main_config, &Table::new(), &system).unwrap();
+        assert_eq!(result.config.colors.window_background, "#f5f5f5");
+        system.dark = true;
+        system.accent = Some("#3584e4".to_string());
+        let result = Config::load(&main_config, &Table::new(), &system).unwrap();
+        assert_eq!(result.config.colors.window_background, "#282828");
+        assert_eq!(result.config.colors.clock_hand_color, "#3584e4");
+        assert_eq!(result.config.colors.separator_color, "#3584e4");
+        assert_eq!(result.config.colors.clock_bg, "#010203");
+
+        fs::write(&main_config, "[theme]\nname = \"nope\"\n").unwrap();
+        let err = Config::load(&main_config, &Table::new(), &SystemStyle::default()).unwrap_err();
+        assert_eq!(err.diagnostics.len(), 1);
+        assert_eq!(err.diagnostics[0].position, Some((2, 1)));
+        assert!(
+            err.diagnostics[0]
+                .to_string()
+                .contains("unknown theme 'nope'")
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_load_color_schedule() {
+        let dir = std::env::temp_dir().join("chronomatrix_test_schedule");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let main_config = dir.join("config.toml");
+
+        fs::write(
+            &main_config,
+            "[colors]\nschedule_mode = \"step\"\n\n\
+             [[colors.schedule]]\ntime = \"07:00\"\nclock_hand_color = \"#7aa2f7\"\n",
+        )
+        .unwrap();
+        let result = Config::load(&main_config, &Table::new(), &SystemStyle::default()).unwrap();
+        let colors = &result.config.colors;
+        assert_eq!(colors.schedule_mode, ScheduleMode::Step);
+        assert_eq!(colors.schedule.len(), 1);
+        assert_eq!(colors.schedule[0].time, "07:00");
+        assert_eq!(
+            colors.schedule[0]
+                .colors
+                .get("clock_hand_color")
+                .map(String::as_str),
+            Some("#7aa2f7")
+        );
+
+        // Problems are located at the entry that has them
+        fs::write(
+            &main_config,
+            "[[colors.schedule]]\ntime = \"07:00\"\n\n\
+             [[colors.schedule]]\ntime = \"25:00\"\nhand = \"#ffffff\"\n",
+        )
+        .unwrap();
+        let err = Config::load(&main_config, &Table::new(), &SystemStyle::default()).unwrap_err();
+        assert_eq!(err.diagnostics.len(), 2);
+        let time = err
+            .diagnostics
+            .iter()
+            .find(|d| d.to_string().contains("invalid time '25:00'"))
+            .unwrap();
+        assert_eq!(time.position, Some((5, 1)));
+        let unknown = err
+            .diagnostics
+            .iter()
+            .find(|d| d.to_string().contains("unknown color 'hand'"))
+            .unwrap();
+        assert_eq!(unknown.position, Some((6, 1)));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_digit_hand_colors() {
+        let config: Config = toml::from_str(
+            r##"
+            [colors]
+            clock_hand_color = "#ff0000"
+            clock_hand_inactive = "#ff000026"
+
+            [colors.seconds]
+            clock_hand_color = "#808080"
+
+            [colors.seconds.ones]
+            clock_hand_inactive = "#00000000"
+
+            [colors.hours.tens]
+            clock_hand_color = "#ffffff"
+
+            [colors.minute_hand]
+            clock_hand_color = "#0000ff"
+            "##,
+        )
+        .unwrap();
+        let colors = &config.colors;
+        let hour = |index| colors.digit_hand_colors(index, Hand::Hour);
+        let minute = |index| colors.digit_hand_colors(index, Hand::Minute);
+        assert_eq!(hour(0), ("#ffffff", "#ff000026"));
+        assert_eq!(hour(1), ("#ff0000", "#ff000026"));
+        assert_eq!(hour(2), ("#ff0000", "#ff000026"));
+        assert_eq!(hour(4), ("#808080", "#ff000026"));
+        assert_eq!(hour(5), ("#808080", "#00000000"));
+        assert_eq!(minute(0), ("#ffffff", "#ff000026"));
+        assert_eq!(minute(1), ("#0000ff", "#ff000026"));
+        assert_eq!(minute(4), ("#808080", "#ff000026"));
+
+        let mut config = config;
+        config.colors.minutes.ones.clock_hand_color = "reddish".to_string();
+        let keys: Vec<String> = config.validate().into_iter().map(|(k, _)| k).collect();
+        assert_eq!(keys, vec!["colors.minutes.ones.clock_hand_color"]);
+    }
+
+    #[test]
+    fn test_load_color_references() {
+        let dir = std::env::temp_dir().join("chronomatrix_test_references");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let main_config = dir.join("config.toml");
+
+        // A reference in the main file sees the palette of an include
+        fs::write(
+            &main_config,
+            "include = [\"palette.toml\"]\n\n[colors]\n\
+             clock_hand_color = \"$accent\"\n\
+             clock_hand_inactive = \"$clock_hand_color / 15%\"\n\
+             separator_color = \"lighten($accent, 100%)\"\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("palette.toml"),
+            "[colors.palette]\naccent = \"tomato\"\n",
+        )
+        .unwrap();
+        let result = Config::load(&main_config, &Table::new(), &SystemStyle::default()).unwrap();
+        let colors = &result.config.colors;
+        assert_eq!(colors.clock_hand_color, "#ff6347");
+        assert_eq!(colors.clock_hand_inactive, "#ff634726");
+        assert_eq!(colors.separator_color, "#ffffff");
+        assert_eq!(colors.palette["accent"], "#ff6347");
+
+        // Overriding the base color carries through to what refers to it
+        let mut source = ConfigSource::default();
+        source.set(
+            "colors",
+            "clock_hand_color",
+            toml::Value::String("#0000ff".to_string()),
+        );
+        let result = Config::load(&main_config, &source.overrides, &source.system).unwrap();
+        assert_eq!(result.config.colors.clock_hand_inactive, "#0000ff26");
+
+        fs::write(
+            &main_config,
+            "[colors]\nclock_bg = \"$clock_border\"\nclock_border = \"$nope\"\n",
+        )
+        .unwrap();
+        let err = Config::load(&main_config, &Table::new(), &SystemStyle::default()).unwrap_err();
+        // Only where the bad reference is written, not for clock_bg or the
+        // theme's display_border referring to clock_border
+        assert_eq!(err.diagnostics.len(), 1);
+        assert!(
+            err.diagnostics[0]
+                .to_string()
+                .contains("unknown color reference '$nope'")
+        );
+        assert_eq!(err.diagnostics[0].position, Some((3, 1)));
+
+        // Every color in a cycle is reported, but not those referring to it
+        fs::write(
+            &main_config,
+            "[colors]\nclock_bg = \"$clock_border\"\n\
+             clock_border = \"$display_bg\"\ndisplay_bg = \"$clock_border\"\n",
+        )
+        .unwrap();
+        let err = Config::load(&main_config, &Table::new(), &SystemStyle::default()).unwrap_err();
+        let positions: Vec<_> = err.diagnostics.iter().map(|d| d.position).collect();
+        assert_eq!(positions, [Some((3, 1)), Some((4, 1))]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_load_backgrounds() {
+        let dir = std::env::temp_dir().join("chronomatrix_test_backgrounds");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let main_config = dir.join("config.toml");
+        fs::write(dir.join("texture.png"), "").unwrap();
+
+        // Images are resolved from the config's directory
+        fs::write(
+            &main_config,
+            "[window.background]\ngradient = \"linear\"\n\
+             gradient_colors = [\"$window_background\", \"#302b63\"]\n\n\
+             [display]\npadding = 12\n\n\
+             [display.background]\nimage = \"texture.png\"\nimage_mode = \"tile\"\n",
+        )
+        .unwrap();
+        let result = Config::load(&main_config, &Table::new(), &SystemStyle::default()).unwrap();
+        let config = &result.config;
+        assert_eq!(config.window.background.gradient, Gradient::Linear);
+        assert_eq!(config.display.padding, 12);
+        assert_eq!(config.display.border_radius, 20);
+        assert_eq!(config.display.background.image_mode, ImageMode::Tile);
+        assert_eq!(
+            Path::new(&config.display.background.image),
+            dir.join("texture.png")
+        );
+        assert_eq!(result.images, vec![dir.join("texture.png")]);
+        assert!(result.source_files.contains(&dir.join("texture.png")));
+        assert!(result.diagnostics.is_empty());
+
+        // A missing image is only a warning
+        fs::write(
+            &main_config,
+            "[window.background]\nimage = \"missing.png\"\n",
+        )
+        .unwrap();
+        let result = Config::load(&main_config, &Table::new(), &SystemStyle::default()).unwrap();
+        assert_eq!(result.diagnostics.len(), 1);
+        assert_eq!(result.diagnostics[0].position, Some((2, 1)));
+
+        fs::write(
+            &main_config,
+            "[display]\nborder_width = -1\n\n\
+             [display.background]\ngradient = \"radial\"\ngradient_colors = [\"red\"]\n",
+        )
+        .unwrap();
+        let err = Config::load(&main_config, &Table::new(), &SystemStyle::default()).unwrap_err();
+        let messages: Vec<String> = err.diagnostics.iter().map(|d| d.to_string()).collect();
+        assert_eq!(messages.len(), 2, "{:?}", messages);
+        assert!(messages[0].contains("border_width"));
+        assert!(messages[1].contains("a gradient needs at least 2 colors"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_face_overrides() {
+        let config: Config = toml::from_str(
+            r#"
+            [face]
+            style = "rounded-square"
+            ticks = "hours"
+
+            [face.inactive]
+            style = "none"
+            border_width = 0.0
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.face.face(true),
+            Face {
+                style: FaceStyle::RoundedSquare,
+                ticks: TickMarks::Hours,
+                bevel: Bevel::None,
+                border_width: 1.0,
+            }
+        );
+        assert_eq!(
+            config.face.face(false),
+            Face {
+                style: FaceStyle::None,
+                ticks: TickMarks::Hours,
+                bevel: Bevel::None,
+                border_width: 0.0,
+            }
+        );
+
+        let mut config = config;
+        config.face.active.border_width = Some(-1.0);
+        let keys: Vec<String> = config.validate().into_iter().map(|(k, _)| k).collect();
+        assert_eq!(keys, vec!["face.active.border_width"]);
+    }
+
+    #[test]
+    fn test_hand_styles() {
+        let mut config: Config = toml::from_str(
+            r#"
+            [hands.hour]
+            length = 0.6
+            width = 4.0
+            cap = "butt"
+            taper = 0.5
+
+            [hands.minute]
+            tail = 0.2
+            "#,
+        )
+        .unwrap();
+        let hour = config.hands.get(Hand::Hour);
+        assert_eq!(hour.length, 0.6);
+        assert_eq!(hour.cap, HandCap::Butt);
+        let minute = config.hands.get(Hand::Minute);
+        assert_eq!(minute.length, 1.0);
+        assert_eq!(minute.cap, HandCap::Round);
+        assert_eq!(minute.tail, 0.2);
+        assert!(config.validate().is_empty());
+
+        config.hands.hour.taper = 1.5;
+        config.hands.minute.length = 0.0;
+        let keys: Vec<String> = config.validate().into_iter().map(|(k, _)| k).collect();
+        assert_eq!(keys, vec!["hands.hour.taper", "hands.minute.length"]);
+    }
+
+    #[test]
+    fn test_effects_validation() {
+        let mut config = Config::default();
+        assert!(config.validate().is_empty());
+
+        config.effects.glow_radius = -2.0;
+        config.effects.glow_intensity = 1.5;
+        config.effects.trail_intensity = -0.5;
+        config.effects.trail_length = MAX_TRAIL_LENGTH + 1;
+        let keys: Vec<String> = config.validate().into_iter().map(|(k, _)| k).collect();
+        assert_eq!(
+            keys,
+            vec![
+                "effects.glow_radius",
+                "effects.glow_intensity",
+                "effects.trail_intensity",
+                "effects.trail_length"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_display_mode_from_str() {
+        assert_eq!("clock".parse::<DisplayMode>(), Ok(DisplayMode::Clock));
+        assert_eq!("Timer".parse::<DisplayMode>(), Ok(DisplayMode::Timer));
+        assert_eq!(
+            " stopwatch ".parse::<DisplayMode>(),
+            Ok(DisplayMode::Stopwatch)
+        );
+        assert_eq!("ambient".parse::<DisplayMode>(), Ok(DisplayMode::Ambient));
+        assert!("alarm".parse::<DisplayMode>().is_err());
+    }
+
+    #[test]
+    fn test_parse_timezone() {
+        assert_eq!(parse_timezone(""), Ok(None));
+        assert_eq!(parse_timezone("Local"), Ok(None));
+        assert_eq!(
+            parse_timezone("Europe/Berlin"),
+            Ok(Some(Tz::Europe__Berlin))
+        );
+        assert!(parse_timezone("Mars/Olympus").is_err());
+    }
+
+    #[test]
+    fn test_default_config_files_follow_theme() {
+        let dir = std::env::temp_dir().join("chronomatrix_test_default_files");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let bundled = Path::new(env!("CARGO_MANIFEST_DIR")).join("config.toml");
+        let printed = dir.join("config.toml");
+        fs::write(&printed, Config::default_toml().unwrap()).unwrap();
+
+        let mut source = ConfigSource::default();
+        source.set("theme", "name", toml::Value::String("gruvbox".to_string()));
+        for main_config in [&bundled, &printed] {
+            let result = Config::load(main_config, &source.overrides, &source.system).unwrap();
+            assert_eq!(result.config.colors.window_background, "#282828");
+            assert_ne!(
+                result.config.colors.clock_hand_color,
+                ColorConfig::default().clock_hand_color
+            );
+        }
+
+        // The desktop's accent color reaches the hands, inactive ones included
+        // at the dark theme's 12%
+        let mut source = ConfigSource::default();
+        source.set("theme", "follow_system", toml::Value::Boolean(true));
+        source.system = SystemStyle {
+            dark: true,
+            accent: Some("#3584e4".to_string()),
+        };
+        for main_config in [&bundled, &printed] {
+            let result = Config::load(main_config, &source.overrides, &source.system).unwrap();
+            assert_eq!(result.config.colors.clock_hand_color, "#3584e4");
+            assert_eq!(result.config.colors.clock_hand_inactive, "#3584e41f");
+        }
+
+        let _ = fs::remove_dir_all(&dir);
+    }
 }
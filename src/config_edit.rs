@@ -0,0 +1,164 @@
+//! Writing single settings back to the config files.
+//!
+//! Edits go through `toml_edit`, so comments, formatting, key order and the
+//! `include` list survive. A setting is written to the file that currently
+//! decides its value, which is the last include that sets it or else the main
+//! config, so the change isn't shadowed by a theme file.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use toml_edit::{DocumentMut, Item, Table, TableLike, Value};
+
+use crate::diagnostics::key_span;
+
+/// Writes one setting to the config files.
+///
+/// # Arguments
+/// * `files` - The config files in order of increasing precedence, the
+///   main config first (see `ConfigLoadResult::source_files`)
+/// * `key` - Dotted key such as `colors.clock_bg`
+/// * `value` - The new value
+///
+/// # Returns
+/// The file that was written, or a description of why it couldn't be
+pub fn write_setting(files: &[PathBuf], key: &str, value: Value) -> Result<PathBuf, String> {
+    let Some(main) = files.first() else {
+        return Err("no config file".to_string());
+    };
+    let path = files
+        .iter()
+        .rev()
+        .find(|file| {
+            fs::read_to_string(file)
+                .ok()
+                .and_then(|text| key_span(&text, key))
+                .is_some()
+        })
+        .unwrap_or(main);
+
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(format!("{}: {}", path.display(), e)),
+    };
+    let mut doc: DocumentMut = text
+        .parse()
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+
+    set_in_document(&mut doc, key, value).map_err(|e| format!("{}: {}", path.display(), e))?;
+    write_file(path, &doc.to_string()).map_err(|e| format!("{}: {}", path.display(), e))?;
+    Ok(path.clone())
+}
+
+/// Sets a dotted key in a document, creating tables as needed.
+///
+/// An existing value keeps its surrounding whitespace and trailing comment.
+///
+/// # Returns
+/// An error if part of the key is already set to something other than a table
+pub fn set_in_document(doc: &mut DocumentMut, key: &str, value: Value) -> Result<(), String> {
+    let mut parts: Vec<&str> = key.split('.').collect();
+    let Some(name) = parts.pop() else {
+        return Err("empty key".to_string());
+    };
+
+    let mut table: &mut dyn TableLike = doc.as_table_mut();
+    for part in parts {
+        if table.get(part).is_none() {
+            table.insert(part, Item::Table(Table::new()));
+        }
+        table = table
+            .get_mut(part)
+            .and_then(Item::as_table_like_mut)
+            .ok_or_else(|| format!("'{}' is not a table", part))?;
+    }
+
+    match table.get_mut(name).and_then(Item::as_value_mut) {
+        Some(existing) => {
+            let decor = existing.decor().clone();
+            *existing = value;
+            *existing.decor_mut() = decor;
+        }
+        None => {
+            table.insert(name, Item::Value(value));
+        }
+    }
+    Ok(())
+}
+
+fn write_file(path: &Path, contents: &str) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(text: &str, key: &str, value: impl Into<Value>) -> String {
+        let mut doc: DocumentMut = text.parse().unwrap();
+        set_in_document(&mut doc, key, value.into()).unwrap();
+        doc.to_string()
+    }
+
+    #[test]
+    fn test_set_keeps_comments() {
+        let text = "# My clock\n[window]\n# See-through\nopacity = 1.0 # fully opaque\n";
+        assert_eq!(
+            set(text, "window.opacity", 0.5),
+            "# My clock\n[window]\n# See-through\nopacity = 0.5 # fully opaque\n"
+        );
+    }
+
+    #[test]
+    fn test_set_adds_missing_keys_and_tables() {
+        assert_eq!(
+            set("[window]\nopacity = 1.0\n", "window.margin", 8),
+            "[window]\nopacity = 1.0\nmargin = 8\n"
+        );
+        assert_eq!(
+            set("include = [\"theme.toml\"]\n", "clock.size", 30),
+            "include = [\"theme.toml\"]\n\n[clock]\nsize = 30\n"
+        );
+        assert_eq!(
+            set("window.opacity = 1.0\n", "window.opacity", 0.25),
+            "window.opacity = 0.25\n"
+        );
+    }
+
+    #[test]
+    fn test_set_rejects_non_table() {
+        let mut doc: DocumentMut = "clock = 4\n".parse().unwrap();
+        assert!(set_in_document(&mut doc, "clock.size", 30.into()).is_err());
+    }
+
+    #[test]
+    fn test_write_setting_targets_deciding_file() {
+        let dir = std::env::temp_dir().join("chronomatrix_test_config_edit");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let main = dir.join("config.toml");
+        let theme = dir.join("theme.toml");
+        fs::write(&main, "include = [\"theme.toml\"]\n[clock]\nsize = 40\n").unwrap();
+        fs::write(&theme, "[colors]\nclock_bg = \"#000000\"\n").unwrap();
+        let files = vec![main.clone(), theme.clone()];
+
+        let written = write_setting(&files, "colors.clock_bg", "#112233".into()).unwrap();
+        assert_eq!(written, theme);
+        assert!(fs::read_to_string(&theme).unwrap().contains("\"#112233\""));
+
+        let written = write_setting(&files, "window.opacity", 0.5.into()).unwrap();
+        assert_eq!(written, main);
+        assert!(fs::read_to_string(&main).unwrap().contains("opacity = 0.5"));
+
+        // A missing main config is created
+        let fresh = dir.join("new").join("config.toml");
+        write_setting(std::slice::from_ref(&fresh), "clock.size", 30.into()).unwrap();
+        assert_eq!(fs::read_to_string(&fresh).unwrap(), "[clock]\nsize = 30\n");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,113 @@
+//! Keyboard shortcuts and the right-click menu.
+//!
+//! Both only activate `app.*` actions (see `actions`), so everything they
+//! do can also be scripted. Shortcuts come from the `[shortcuts]` config
+//! section and are re-applied on every reload:
+//!
+//! | Key                     | Action                  |
+//! | ----------------------- | ----------------------- |
+//! | `F11`                   | `app.toggle-fullscreen` |
+//! | `Space`                 | `app.toggle-running`    |
+//! | `Backspace`             | `app.reset`             |
+//! | `R`                     | `app.reload-config`     |
+//! | `+` / `-`               | `app.zoom-in` / `-out`  |
+//! | `Ctrl+,`                | `app.preferences`       |
+//! | `T`                     | `app.cycle-theme`       |
+//! | `Q`, `Ctrl+Q`           | `app.quit`              |
+
+use gtk4::prelude::*;
+use gtk4::{Application, PopoverMenu, gdk, gio, glib};
+
+use crate::config::ShortcutConfig;
+use crate::preferences::label;
+use crate::theme;
+
+/// Sets the keyboard shortcuts of all configurable actions.
+///
+/// Accelerators GTK can't parse are skipped with a warning.
+///
+/// # Arguments
+/// * `app` - The application owning the actions
+/// * `shortcuts` - The `[shortcuts]` config section
+pub fn set_shortcuts(app: &Application, shortcuts: &ShortcutConfig) {
+    for (action, accels) in shortcuts.bindings() {
+        let valid: Vec<&str> = accels
+            .iter()
+            .map(String::as_str)
+            .filter(|accel| {
+                let ok = gtk4::accelerator_parse(*accel).is_some();
+                if !ok {
+                    eprintln!(
+                        "Warning: Ignoring invalid shortcut '{}' for {}",
+                        accel, action
+                    );
+                }
+                ok
+            })
+            .collect();
+        app.set_accels_for_action(&format!("app.{}", action), &valid);
+    }
+}
+
+/// Shows the context menu when the widget is right-clicked.
+///
+/// # Arguments
+/// * `widget` - The widget that receives the clicks and anchors the menu
+pub fn setup_context_menu(widget: &impl IsA<gtk4::Widget>) {
+    let popover = PopoverMenu::from_model(Some(&menu_model()));
+    popover.set_parent(widget);
+    popover.set_has_arrow(false);
+
+    let click = gtk4::GestureClick::new();
+    click.set_button(gdk::BUTTON_SECONDARY);
+    click.connect_pressed(glib::clone!(
+        #[weak]
+        popover,
+        move |gesture, _, x, y| {
+            // Claim the click so the window handle doesn't show its own menu
+            gesture.set_state(gtk4::EventSequenceState::Claimed);
+            popover.set_pointing_to(Some(&gdk::Rectangle::new(x as i32, y as i32, 1, 1)));
+            popover.popup();
+        }
+    ));
+    widget.add_controller(click);
+}
+
+/// Builds the context menu, with one section per group of actions.
+fn menu_model() -> gio::Menu {
+    let modes = gio::Menu::new();
+    modes.append(Some("Clock"), Some("app.set-mode::clock"));
+    modes.append(Some("Timer"), Some("app.set-mode::timer"));
+    modes.append(Some("Stopwatch"), Some("app.set-mode::stopwatch"));
+    modes.append(Some("Ambient"), Some("app.set-mode::ambient"));
+
+    let running = gio::Menu::new();
+    running.append(Some("Start/Pause"), Some("app.toggle-running"));
+    running.append(Some("Reset"), Some("app.reset"));
+
+    let view = gio::Menu::new();
+    view.append(Some("Larger"), Some("app.zoom-in"));
+    view.append(Some("Smaller"), Some("app.zoom-out"));
+    view.append(Some("Fullscreen"), Some("app.toggle-fullscreen"));
+
+    let themes = gio::Menu::new();
+    for (name, _) in theme::BUILTIN {
+        let item = gio::MenuItem::new(Some(&label(name, '-')), None);
+        item.set_action_and_target_value(Some("app.set-theme"), Some(&name.to_variant()));
+        themes.append_item(&item);
+    }
+    themes.append(Some("Next Theme"), Some("app.cycle-theme"));
+    view.append_submenu(Some("Theme"), &themes);
+
+    let app = gio::Menu::new();
+    app.append(Some("Preferences"), Some("app.preferences"));
+    app.append(Some("Reload Config"), Some("app.reload-config"));
+    app.append(Some("Quit"), Some("app.quit"));
+
+    let menu = gio::Menu::new();
+    menu.append_section(None, &modes);
+    menu.append_section(None, &running);
+    menu.append_section(None, &view);
+    menu.append_section(None, &app);
+    menu
+}
@@ -0,0 +1,287 @@
+//! Human-readable reports of configuration problems.
+//!
+//! Loading the configuration produces `Diagnostic`s rather than a single
+//! opaque error, so every problem can be reported together with the file,
+//! line and column it comes from:
+//!
+//! ```text
+//! ~/.config/chronomatrix/config.toml:12:11: error: window.opacity: must be between 0.0 and 1.0 (got 1.5)
+//! ~/.config/chronomatrix/theme.toml:3:1: warning: unknown key 'colors.hand_colour'
+//! ```
+//!
+//! Errors make a config unusable (the previous or default config is kept);
+//! warnings are reported but the config is still applied.
+
+use std::fmt;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use toml::value::Table;
+
+/// How serious a problem is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// The configuration cannot be applied
+    Error,
+    /// The problem is ignored, e.g. an unknown key
+    Warning,
+}
+
+/// A single problem found while loading the configuration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// File containing the problem, or `None` for overrides and defaults
+    pub file: Option<PathBuf>,
+    /// 1-based line and column within `file`
+    pub position: Option<(usize, usize)>,
+    pub message: String,
+}
+
+impl Diagnostic {
+    /// Creates an error located in a file.
+    ///
+    /// # Arguments
+    /// * `file` - The file the problem is in
+    /// * `text` - The file's contents, used to turn `span` into line and column
+    /// * `span` - Byte range of the offending text, if known
+    /// * `message` - Description of the problem
+    pub fn error_in(
+        file: &Path,
+        text: &str,
+        span: Option<Range<usize>>,
+        message: impl Into<String>,
+    ) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            file: Some(file.to_path_buf()),
+            position: span.map(|s| line_col(text, s.start)),
+            message: message.into(),
+        }
+    }
+
+    /// Creates a warning located in a file. See `error_in`.
+    pub fn warning_in(
+        file: &Path,
+        text: &str,
+        span: Option<Range<usize>>,
+        message: impl Into<String>,
+    ) -> Self {
+        Diagnostic {
+            severity: Severity::Warning,
+            ..Self::error_in(file, text, span, message)
+        }
+    }
+
+    /// Creates an error that is not tied to a file.
+    pub fn error(message: impl Into<String>) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            file: None,
+            position: None,
+            message: message.into(),
+        }
+    }
+
+    /// Returns whether this diagnostic prevents the config from being used.
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}:", file.display())?;
+            if let Some((line, column)) = self.position {
+                write!(f, "{}:{}:", line, column)?;
+            }
+            f.write_str(" ")?;
+        }
+
+        let severity = match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        };
+        write!(f, "{}: {}", severity, self.message)
+    }
+}
+
+/// A configuration that could not be loaded.
+#[derive(Debug)]
+pub struct ConfigError {
+    /// Every problem found, including warnings; at least one is an error
+    pub diagnostics: Vec<Diagnostic>,
+    /// Files read before the failure, so they can still be watched for fixes
+    pub source_files: Vec<PathBuf>,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, diagnostic) in self.diagnostics.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", diagnostic)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Converts a byte offset into a 1-based line and column.
+pub fn line_col(text: &str, offset: usize) -> (usize, usize) {
+    let before = &text[..offset.min(text.len())];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    let column = before[line_start..].chars().count() + 1;
+    (line, column)
+}
+
+/// Finds where a dotted key such as `window.opacity` is set in a TOML file.
+///
+/// Entries of an array of tables are addressed by index, so
+/// `colors.schedule.1.time` is the `time` key of the second
+/// `[[colors.schedule]]`.
+///
+/// # Returns
+/// The byte range of the key, or `None` if the file doesn't set it
+pub fn key_span(text: &str, path: &str) -> Option<Range<usize>> {
+    let doc = toml_edit::ImDocument::parse(text).ok()?;
+    let mut table: &dyn toml_edit::TableLike = doc.as_table();
+    let mut parts = path.split('.').peekable();
+
+    while let Some(part) = parts.next() {
+        let (key, item) = table.get_key_value(part)?;
+        if let Some(array) = item.as_array_of_tables()
+            && let Some(index) = parts.peek().and_then(|next| next.parse::<usize>().ok())
+        {
+            parts.next();
+            let entry = array.get(index)?;
+            if parts.peek().is_none() {
+                return entry.span();
+            }
+            table = entry;
+            continue;
+        }
+        if parts.peek().is_none() {
+            return key.span().or_else(|| item.span());
+        }
+        table = item.as_table_like()?;
+    }
+
+    None
+}
+
+/// Lists keys in `table` that don't exist in `schema`.
+///
+/// Tables in the schema are checked recursively. An empty schema table
+/// accepts any keys, for sections whose keys are user-defined.
+///
+/// # Arguments
+/// * `table` - The table read from a file
+/// * `schema` - The default config serialized to a table
+/// * `prefix` - Dotted path of `table`, empty at the top level
+///
+/// # Returns
+/// The dotted paths of all unknown keys
+pub fn unknown_keys(table: &Table, schema: &Table, prefix: &str) -> Vec<String> {
+    let mut unknown = Vec::new();
+
+    for (key, value) in table {
+        let path = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+
+        match (schema.get(key), value) {
+            (None, _) => unknown.push(path),
+            (Some(toml::Value::Table(section)), toml::Value::Table(inner))
+                if !section.is_empty() =>
+            {
+                unknown.extend(unknown_keys(inner, section, &path));
+            }
+            _ => {}
+        }
+    }
+
+    unknown
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_col() {
+        let text = "a = 1\nb = 2\n";
+        assert_eq!(line_col(text, 0), (1, 1));
+        assert_eq!(line_col(text, 4), (1, 5));
+        assert_eq!(line_col(text, 6), (2, 1));
+        assert_eq!(line_col(text, 100), (3, 1));
+    }
+
+    #[test]
+    fn test_key_span() {
+        let text = "[window]\nopacity = 2.0\n\n[clock]\nsize = 4\n";
+        assert_eq!(
+            key_span(text, "window.opacity").map(|s| line_col(text, s.start)),
+            Some((2, 1))
+        );
+        assert_eq!(
+            key_span(text, "clock.size").map(|s| line_col(text, s.start)),
+            Some((5, 1))
+        );
+        assert_eq!(key_span(text, "clock.gap"), None);
+    }
+
+    #[test]
+    fn test_key_span_dotted_and_inline() {
+        let text = "window.opacity = 2.0\nclock = { size = 4 }\n";
+        assert_eq!(
+            key_span(text, "window.opacity").map(|s| line_col(text, s.start)),
+            Some((1, 8))
+        );
+        assert_eq!(
+            key_span(text, "clock.size").map(|s| line_col(text, s.start)),
+            Some((2, 11))
+        );
+    }
+
+    #[test]
+    fn test_unknown_keys() {
+        let schema: Table = toml::from_str("[window]\nopacity = 1.0\n[themes]\n").unwrap();
+        let table: Table = toml::from_str(
+            "colour = 1\n[window]\nopacity = 0.5\nopacty = 0.5\n[themes]\nmine = 1\n",
+        )
+        .unwrap();
+
+        let mut unknown = unknown_keys(&table, &schema, "");
+        unknown.sort();
+        assert_eq!(unknown, vec!["colour", "window.opacty"]);
+    }
+
+    #[test]
+    fn test_diagnostic_display() {
+        let file = PathBuf::from("config.toml");
+        let text = "[window]\nopacity = 2.0\n";
+        let diagnostic = Diagnostic::error_in(&file, text, Some(9..16), "bad");
+        assert_eq!(diagnostic.to_string(), "config.toml:2:1: error: bad");
+        assert_eq!(Diagnostic::error("bad").to_string(), "error: bad");
+    }
+
+    #[test]
+    fn test_key_span_array_of_tables() {
+        let text = "[[colors.schedule]]\ntime = \"07:00\"\n\n[[colors.schedule]]\ntime = \"9\"\n";
+        assert_eq!(
+            key_span(text, "colors.schedule.1.time").map(|s| line_col(text, s.start)),
+            Some((5, 1))
+        );
+        assert_eq!(
+            key_span(text, "colors.schedule.0").map(|s| line_col(text, s.start)),
+            Some((1, 1))
+        );
+        assert_eq!(key_span(text, "colors.schedule.2.time"), None);
+    }
+}
@@ -25,8 +25,10 @@
 use gtk4::prelude::*;
 use gtk4::{Grid, Widget};
 
-use crate::analog_clock::{AnalogClock, ClockColors};
+use crate::analog_clock::{AnalogClock, ClockColors, ClockFaces, ClockStyle};
+use crate::config::{EffectsConfig, HandsConfig};
 use crate::digit_patterns::get_digit_pattern;
+use crate::glyph_patterns::ClockColumn;
 
 pub struct DigitDisplay {
     container: Grid,
@@ -40,21 +42,13 @@ impl DigitDisplay {
     /// to display digit shapes (0-9).
     ///
     /// # Arguments
-    /// * `size` - Size of each individual clock in pixels
-    /// * `stroke_width` - Width of clock hands in pixels
+    /// * `style` - Style of each clock
     /// * `gap` - Spacing between clocks in pixels
-    /// * `colors` - Color scheme for active/inactive clocks
     /// * `animation_duration_ms` - Duration of hand rotation animations
     ///
     /// # Returns
     /// A new `DigitDisplay` ready to display any digit 0-9
-    pub fn new(
-        size: i32,
-        stroke_width: f64,
-        gap: i32,
-        colors: ClockColors,
-        animation_duration_ms: u64,
-    ) -> Self {
+    pub fn new(style: ClockStyle, gap: i32, animation_duration_ms: u64) -> Self {
         let container = Grid::new();
         container.set_row_spacing(gap as u32);
         container.set_column_spacing(gap as u32);
@@ -65,8 +59,7 @@ impl DigitDisplay {
         for row in 0..6 {
             let mut row_clocks = Vec::new();
             for col in 0..4 {
-                let clock =
-                    AnalogClock::new(size, stroke_width, colors.clone(), animation_duration_ms);
+                let clock = AnalogClock::new(style.clone(), animation_duration_ms);
                 container.attach(&clock, col, row, 1, 1);
                 row_clocks.push(clock);
             }
@@ -117,6 +110,94 @@ impl DigitDisplay {
         }
     }
 
+    /// Animates the hands of every clock to the same angles, e.g. to show
+    /// the time as ordinary analog clocks.
+    ///
+    /// # Arguments
+    /// * `hour` - Hour hand angle in degrees (0-359)
+    /// * `minute` - Minute hand angle in degrees (0-359)
+    pub fn set_angles(&self, hour: i32, minute: i32) {
+        for clock in self.clocks.iter().flatten() {
+            clock.set_angles(hour, minute);
+        }
+    }
+
+    /// Sets the hands of every clock to the same angles without animation.
+    ///
+    /// # Arguments
+    /// * `hour` - Hour hand angle in degrees (0-359)
+    /// * `minute` - Minute hand angle in degrees (0-359)
+    pub fn set_angles_immediate(&self, hour: i32, minute: i32) {
+        for clock in self.clocks.iter().flatten() {
+            clock.set_angles_immediate(hour, minute);
+        }
+    }
+
+    /// Changes the colors of every clock, cross-fading from the current ones.
+    pub fn set_colors(&self, colors: &ClockColors) {
+        for clock in self.clocks.iter().flatten() {
+            clock.set_colors(colors.clone());
+        }
+    }
+
+    /// Changes the face styles of every clock.
+    pub fn set_faces(&self, faces: ClockFaces) {
+        for clock in self.clocks.iter().flatten() {
+            clock.set_faces(faces);
+        }
+    }
+
+    /// Changes the hand width of every clock.
+    pub fn set_stroke_width(&self, stroke_width: f64) {
+        for clock in self.clocks.iter().flatten() {
+            clock.set_stroke_width(stroke_width);
+        }
+    }
+
+    /// Changes the hand shapes of every clock.
+    pub fn set_hands(&self, hands: HandsConfig) {
+        for clock in self.clocks.iter().flatten() {
+            clock.set_hands(hands);
+        }
+    }
+
+    /// Changes the hand glow and shadow of every clock.
+    pub fn set_effects(&self, effects: EffectsConfig) {
+        for clock in self.clocks.iter().flatten() {
+            clock.set_effects(effects);
+        }
+    }
+
+    /// Changes the hand rotation duration of every clock.
+    pub fn set_animation_duration(&self, animation_duration_ms: u64) {
+        for clock in self.clocks.iter().flatten() {
+            clock.set_animation_duration(animation_duration_ms);
+        }
+    }
+
+    /// Returns the number of clock columns in this display.
+    pub fn column_count(&self) -> usize {
+        self.clocks.first().map_or(0, |row| row.len())
+    }
+
+    /// Animates a single column of clocks to the given hand positions.
+    ///
+    /// Used for content that does not line up with digit boundaries, such as
+    /// scrolling text. Columns outside the grid are ignored.
+    ///
+    /// # Arguments
+    /// * `col` - Column index (0 = leftmost)
+    /// * `column` - Hand positions for each row, top to bottom
+    pub fn set_column(&self, col: usize, column: &ClockColumn) {
+        for (row_idx, clock_pos) in column.iter().enumerate() {
+            if let Some(clock_row) = self.clocks.get(row_idx)
+                && let Some(clock) = clock_row.get(col)
+            {
+                clock.set_angles(clock_pos.hour, clock_pos.minute);
+            }
+        }
+    }
+
     /// Returns a reference to the root widget for this digit display.
     ///
     /// # Returns
@@ -0,0 +1,207 @@
+//! Text glyphs for scrolling messages on the clock grid.
+//!
+//! Digits shown as the time use the bold 6x4 patterns in `digit_patterns`.
+//! Free-form messages need many more characters, so this module defines a
+//! compact single-stroke font where every glyph is 6 rows tall and 1-5
+//! columns wide.
+//!
+//! Glyphs are drawn with box-drawing characters, one character per analog
+//! clock. Each character maps to a pair of hand angles:
+//!
+//! | Char | Hands       | Char | Hands       |
+//! | ---- | ----------- | ---- | ----------- |
+//! | `┌`  | right, down | `╵`  | up stub     |
+//! | `┐`  | down, left  | `╷`  | down stub   |
+//! | `└`  | up, right   | `╴`  | left stub   |
+//! | `┘`  | up, left    | `╶`  | right stub  |
+//! | `─`  | left, right | `╱`  | NE-SW       |
+//! | `│`  | up, down    | `╲`  | NW-SE       |
+//!
+//! A space is an inactive clock.
+
+use crate::digit_patterns::ClockPosition;
+
+/// Number of clock rows in a glyph (matches the height of a digit)
+pub const GLYPH_ROWS: usize = 6;
+
+/// A single column of clocks, top to bottom
+pub type ClockColumn = [ClockPosition; GLYPH_ROWS];
+
+/// A column with every clock at rest
+pub const BLANK_COLUMN: ClockColumn = [ClockPosition::INACTIVE; GLYPH_ROWS];
+
+/// Number of blank columns inserted between adjacent glyphs
+const GLYPH_SPACING: usize = 1;
+
+/// Converts a box-drawing character into clock hand angles.
+///
+/// The NW-SE diagonal uses (315°, 135°) rather than the inactive (135°, 315°)
+/// so it is drawn in the active color. Unknown characters are inactive.
+fn cell_position(cell: char) -> ClockPosition {
+    match cell {
+        '┌' => ClockPosition::new(90, 180),
+        '┐' => ClockPosition::new(180, 270),
+        '└' => ClockPosition::new(0, 90),
+        '┘' => ClockPosition::new(0, 270),
+        '─' => ClockPosition::new(90, 270),
+        '│' => ClockPosition::new(0, 180),
+        '╵' => ClockPosition::new(0, 0),
+        '╷' => ClockPosition::new(180, 180),
+        '╴' => ClockPosition::new(270, 270),
+        '╶' => ClockPosition::new(90, 90),
+        '╱' => ClockPosition::new(45, 225),
+        '╲' => ClockPosition::new(315, 135),
+        _ => ClockPosition::INACTIVE,
+    }
+}
+
+/// Returns the glyph rows for a character.
+///
+/// Letters are case-insensitive. Characters without a glyph render as `?`.
+fn get_glyph(ch: char) -> [&'static str; GLYPH_ROWS] {
+    match ch.to_ascii_uppercase() {
+        'A' => ["┌─┐", "│ │", "│ │", "│─│", "│ │", "╵ ╵"],
+        'B' => ["┌─┐", "│ │", "│─┘", "│─┐", "│ │", "└─┘"],
+        'C' => ["┌─╴", "│  ", "│  ", "│  ", "│  ", "└─╴"],
+        'D' => ["──┐", "│ │", "│ │", "│ │", "│ │", "──┘"],
+        'E' => ["┌─╴", "│  ", "│─╴", "│  ", "│  ", "└─╴"],
+        'F' => ["┌─╴", "│  ", "│─╴", "│  ", "│  ", "╵  "],
+        'G' => ["┌─╴", "│  ", "│  ", "│╶┐", "│ │", "└─┘"],
+        'H' => ["╷ ╷", "│ │", "│ │", "│─│", "│ │", "╵ ╵"],
+        'I' => ["╷", "│", "│", "│", "│", "╵"],
+        'J' => ["  ╷", "  │", "  │", "  │", "╷ │", "└─┘"],
+        'K' => ["╷  ", "│ ╱", "│╱ ", "│╲ ", "│ ╲", "╵  "],
+        'L' => ["╷  ", "│  ", "│  ", "│  ", "│  ", "└─╴"],
+        'M' => ["┌───┐", "│ │ │", "│ │ │", "│ │ │", "│ │ │", "╵ ╵ ╵"],
+        'N' => ["╷  ╷", "│╲ │", "│╲ │", "│ ╲│", "│ ╲│", "╵  ╵"],
+        'O' => ["┌─┐", "│ │", "│ │", "│ │", "│ │", "└─┘"],
+        'P' => ["┌─┐", "│ │", "│─┘", "│  ", "│  ", "╵  "],
+        'Q' => ["┌─┐", "│ │", "│ │", "│ │", "│╲│", "└─╲"],
+        'R' => ["┌─┐", "│ │", "│─┘", "│╲ ", "│ ╲", "╵  "],
+        'S' => ["┌─╴", "│  ", "└─┐", "  │", "  │", "╶─┘"],
+        'T' => ["───", " │ ", " │ ", " │ ", " │ ", " ╵ "],
+        'U' => ["╷ ╷", "│ │", "│ │", "│ │", "│ │", "└─┘"],
+        'V' => ["╷  ╷", "│  │", "│  │", "│  │", "╲  ╱", " ╲╱ "],
+        'W' => ["╷   ╷", "│   │", "│ ╷ │", "│ │ │", "│ │ │", "└───┘"],
+        'X' => ["╷  ╷", "╲  ╱", " ╲╱ ", " ╱╲ ", "╱  ╲", "╵  ╵"],
+        'Y' => ["╷ ╷", "│ │", "╲ ╱", " │ ", " │ ", " ╵ "],
+        'Z' => ["╶─┐", "  ╱", " ╱ ", " ╱ ", "╱  ", "└─╴"],
+        '0' => ["┌─┐", "│ │", "│╱│", "│ │", "│ │", "└─┘"],
+        '1' => ["╶┐", " │", " │", " │", " │", " ╵"],
+        '2' => ["┌─┐", "  │", "┌─┘", "│  ", "│  ", "└─╴"],
+        '3' => ["╶─┐", "  │", "╶─┘", "╶─┐", "  │", "╶─┘"],
+        '4' => ["╷ ╷", "│ │", "└─┐", "  │", "  │", "  ╵"],
+        '5' => ["┌─╴", "│  ", "└─┐", "  │", "  │", "╶─┘"],
+        '6' => ["┌─╴", "│  ", "│─┐", "│ │", "│ │", "└─┘"],
+        '7' => ["╶─┐", "  │", "  │", "  │", "  │", "  ╵"],
+        '8' => ["┌─┐", "│ │", "└─┘", "┌─┐", "│ │", "└─┘"],
+        '9' => ["┌─┐", "│ │", "└─│", "  │", "  │", "╶─┘"],
+        ' ' => ["  ", "  ", "  ", "  ", "  ", "  "],
+        '.' => [" ", " ", " ", " ", " ", "╵"],
+        ',' => [" ", " ", " ", " ", "╷", "╱"],
+        ':' => [" ", "╷", " ", " ", "╷", " "],
+        '!' => ["╷", "│", "│", "│", " ", "╵"],
+        '-' => ["   ", "   ", "╶─╴", "   ", "   ", "   "],
+        '/' => ["  ╱", "  ╱", " ╱ ", " ╱ ", "╱  ", "╱  "],
+        _ => ["┌─┐", "  │", " ┌┘", " │ ", "   ", " ╵ "],
+    }
+}
+
+/// Renders a message into clock columns, left to right.
+///
+/// Each glyph is followed by `GLYPH_SPACING` blank columns so adjacent
+/// characters stay legible.
+///
+/// # Arguments
+/// * `text` - The message to render
+///
+/// # Returns
+/// The message as a sequence of clock columns
+pub fn render_text(text: &str) -> Vec<ClockColumn> {
+    let mut columns = Vec::new();
+
+    for ch in text.chars() {
+        let glyph = get_glyph(ch);
+        let rows: Vec<Vec<char>> = glyph.iter().map(|row| row.chars().collect()).collect();
+        let width = rows[0].len();
+
+        for col in 0..width {
+            let mut column = BLANK_COLUMN;
+            for (row_idx, row) in rows.iter().enumerate() {
+                column[row_idx] = cell_position(row.get(col).copied().unwrap_or(' '));
+            }
+            columns.push(column);
+        }
+
+        columns.extend(std::iter::repeat_n(BLANK_COLUMN, GLYPH_SPACING));
+    }
+
+    columns
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SUPPORTED: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789 .,:!-/";
+
+    #[test]
+    fn test_glyph_rows_have_equal_width() {
+        for ch in SUPPORTED.chars() {
+            let glyph = get_glyph(ch);
+            let width = glyph[0].chars().count();
+            assert!(width > 0, "Glyph {:?} is empty", ch);
+            for row in glyph.iter() {
+                assert_eq!(row.chars().count(), width, "Glyph {:?} has ragged rows", ch);
+            }
+        }
+    }
+
+    #[test]
+    fn test_glyph_cells_are_known() {
+        for ch in SUPPORTED.chars() {
+            for row in get_glyph(ch).iter() {
+                for cell in row.chars() {
+                    assert!(
+                        cell == ' ' || cell_position(cell).hour != ClockPosition::INACTIVE.hour,
+                        "Glyph {:?} uses unmapped cell {:?}",
+                        ch,
+                        cell
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_render_text_width() {
+        // "I" is 1 column wide, "O" is 3, each followed by one spacing column
+        assert_eq!(
+            render_text("IO").len(),
+            1 + GLYPH_SPACING + 3 + GLYPH_SPACING
+        );
+        assert!(render_text("").is_empty());
+    }
+
+    #[test]
+    fn test_render_text_case_insensitive() {
+        let upper = render_text("HI");
+        let lower = render_text("hi");
+        assert_eq!(upper.len(), lower.len());
+        for (a, b) in upper.iter().zip(lower.iter()) {
+            for (pa, pb) in a.iter().zip(b.iter()) {
+                assert_eq!((pa.hour, pa.minute), (pb.hour, pb.minute));
+            }
+        }
+    }
+
+    #[test]
+    fn test_render_text_space_is_inactive() {
+        for column in render_text(" ") {
+            for pos in column.iter() {
+                assert_eq!(pos.hour, ClockPosition::INACTIVE.hour);
+                assert_eq!(pos.minute, ClockPosition::INACTIVE.minute);
+            }
+        }
+    }
+}
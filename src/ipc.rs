@@ -0,0 +1,593 @@
+//! Line-delimited JSON control socket.
+//!
+//! Listens on `$XDG_RUNTIME_DIR/chronomatrix.sock` so status-bar scripts and
+//! shell tools can talk to the clock without D-Bus. Each line sent is one
+//! JSON request; each line received is a response or a subscribed event.
+//!
+//! # Requests
+//! ```text
+//! {"command": "set-mode", "mode": "stopwatch"}
+//! {"command": "start-timer", "duration": "5m"}
+//! {"command": "toggle-running"}
+//! {"command": "reset"}
+//! {"command": "show-message", "text": "DEPLOY FREEZE"}
+//! {"command": "set-colors", "colors": {"clock_hand_color": "#7aa2f7"}}
+//! {"command": "set-theme", "name": "nord"}
+//! {"command": "reload-config"}
+//! {"command": "get-state"}
+//! {"command": "subscribe", "events": ["tick", "alarm", "config-reloaded"]}
+//! ```
+//!
+//! Every request may carry an `"id"`, echoed back in its response. Responses
+//! are `{"ok": true, ...}` or `{"ok": false, "error": "..."}`.
+//!
+//! # Access
+//! Only the user running the clock can connect: the socket is mode 0600, in
+//! the runtime directory or, without one, a private `chronomatrix-<uid>`
+//! directory in the temp directory. A socket that still answers belongs to
+//! another running instance and is left alone.
+//!
+//! # Threading
+//! Connections are served on background threads, which only parse requests
+//! and queue them. The GTK main loop polls the queue, the same way config
+//! reloads are picked up, so all widget access stays on the GTK thread.
+
+use gtk4::prelude::*;
+use gtk4::{Application, glib};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, BufRead, BufReader, Write};
+use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{Sender, channel};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use crate::AppContext;
+use crate::config::{COLOR_KEYS, DisplayMode};
+use crate::mode::parse_duration;
+
+/// Socket file name inside the runtime directory
+const SOCKET_NAME: &str = "chronomatrix.sock";
+
+/// Polling interval for queued requests in milliseconds
+const IPC_POLL_INTERVAL_MS: u64 = 50;
+
+/// Names accepted by the `subscribe` command
+const EVENT_NAMES: [&str; 3] = ["tick", "alarm", "config-reloaded"];
+
+/// A command received over the socket.
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(tag = "command", rename_all = "kebab-case")]
+enum Request {
+    SetMode {
+        mode: String,
+    },
+    StartTimer {
+        duration: String,
+    },
+    ToggleRunning,
+    Reset,
+    ShowMessage {
+        text: String,
+    },
+    SetColors {
+        colors: BTreeMap<String, String>,
+    },
+    SetTheme {
+        name: String,
+    },
+    ReloadConfig,
+    GetState,
+    Subscribe {
+        #[serde(default)]
+        events: Vec<String>,
+    },
+}
+
+/// A request waiting for the GTK main loop.
+struct PendingRequest {
+    /// Client-supplied correlation id, echoed in the response
+    id: Option<Value>,
+    request: Request,
+    /// Outgoing line queue of the connection the request came from
+    reply: Sender<String>,
+}
+
+/// Something that happened, pushed to subscribed connections.
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "kebab-case")]
+pub enum Event {
+    /// The display was updated (once per second)
+    Tick { mode: String, reading: String },
+    /// A countdown timer ran out
+    Alarm,
+    /// The configuration files were reloaded
+    ConfigReloaded,
+}
+
+impl Event {
+    /// Returns the name clients subscribe with.
+    fn name(&self) -> &'static str {
+        match self {
+            Event::Tick { .. } => "tick",
+            Event::Alarm => "alarm",
+            Event::ConfigReloaded => "config-reloaded",
+        }
+    }
+}
+
+/// A connection's event subscription.
+struct Subscriber {
+    /// Event names to deliver (empty for all)
+    events: Vec<String>,
+    sender: Sender<String>,
+}
+
+/// Fans events out to subscribed connections. Lives on the GTK thread.
+#[derive(Default)]
+pub struct EventBus {
+    subscribers: RefCell<Vec<Subscriber>>,
+}
+
+impl EventBus {
+    fn subscribe(&self, events: Vec<String>, sender: Sender<String>) {
+        self.subscribers
+            .borrow_mut()
+            .push(Subscriber { events, sender });
+    }
+
+    /// Sends an event to every interested subscriber.
+    ///
+    /// Subscribers whose connection has closed are dropped.
+    pub fn publish(&self, event: &Event) {
+        let mut subscribers = self.subscribers.borrow_mut();
+        if subscribers.is_empty() {
+            return;
+        }
+
+        let Ok(line) = serde_json::to_string(event) else {
+            return;
+        };
+
+        subscribers.retain(|s| {
+            let wanted = s.events.is_empty() || s.events.iter().any(|e| e == event.name());
+            !wanted || s.sender.send(line.clone()).is_ok()
+        });
+    }
+}
+
+/// Returns the socket path: `$XDG_RUNTIME_DIR/chronomatrix.sock`.
+///
+/// Where no runtime directory exists, the socket goes in a directory only
+/// the current user can enter, `chronomatrix-<uid>` in the temp directory,
+/// which is created if needed.
+///
+/// # Returns
+/// * `Ok(PathBuf)` - Where the socket lives
+/// * `Err(String)` - The private directory could not be set up safely
+pub fn socket_path() -> Result<PathBuf, String> {
+    if let Some(dir) = dirs::runtime_dir() {
+        return Ok(dir.join(SOCKET_NAME));
+    }
+
+    let uid = fs::metadata("/proc/self")
+        .map_err(|e| format!("Could not determine the user id: {}", e))?
+        .uid();
+    let dir = std::env::temp_dir().join(format!("chronomatrix-{}", uid));
+    match fs::DirBuilder::new().mode(0o700).create(&dir) {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {}
+        Err(e) => return Err(format!("Could not create {:?}: {}", dir, e)),
+    }
+    check_private_dir(&dir, uid)?;
+    Ok(dir.join(SOCKET_NAME))
+}
+
+/// Checks that a directory is a real directory, owned by `uid` and closed
+/// to everyone else, so nobody else can reach or replace a socket in it.
+fn check_private_dir(dir: &Path, uid: u32) -> Result<(), String> {
+    let metadata =
+        fs::symlink_metadata(dir).map_err(|e| format!("Could not inspect {:?}: {}", dir, e))?;
+    if !metadata.is_dir() || metadata.uid() != uid || metadata.mode() & 0o077 != 0 {
+        return Err(format!(
+            "{:?} is not a private directory of the current user",
+            dir
+        ));
+    }
+    Ok(())
+}
+
+/// Clears the way for binding the socket at `path`.
+///
+/// A socket file left behind by a crashed instance refuses connections and
+/// is removed. One that accepts a connection belongs to a running instance.
+///
+/// # Returns
+/// * `Ok(())` - Nothing is in the way anymore
+/// * `Err(String)` - Another instance serves the socket, or it is unusable
+fn remove_stale_socket(path: &Path) -> Result<(), String> {
+    match UnixStream::connect(path) {
+        Ok(_) => Err(format!("{:?} is in use by another instance", path)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(e) if e.kind() == io::ErrorKind::ConnectionRefused => fs::remove_file(path)
+            .map_err(|e| format!("Could not remove stale socket {:?}: {}", path, e)),
+        Err(e) => Err(format!("Could not check socket {:?}: {}", path, e)),
+    }
+}
+
+/// Opens the control socket and starts dispatching requests.
+///
+/// Failure to bind is reported but not fatal; the clock runs without IPC.
+/// A stale socket file is replaced, a live one is left to its instance.
+/// The socket file is removed when the application shuts down.
+///
+/// # Arguments
+/// * `app` - The application, used to clean up on shutdown
+/// * `ctx` - Shared handles the requests operate on
+pub fn start(app: &Application, ctx: &AppContext) {
+    let path = match socket_path().and_then(|path| {
+        remove_stale_socket(&path)?;
+        Ok(path)
+    }) {
+        Ok(path) => path,
+        Err(e) => {
+            eprintln!("Failed to open IPC socket: {}", e);
+            return;
+        }
+    };
+
+    let listener = match UnixListener::bind(&path) {
+        Ok(l) => l,
+        Err(e) => {
+            eprintln!("Failed to open IPC socket {:?}: {}", path, e);
+            return;
+        }
+    };
+    if let Err(e) = fs::set_permissions(&path, fs::Permissions::from_mode(0o600)) {
+        eprintln!("Failed to restrict IPC socket {:?}: {}", path, e);
+        let _ = fs::remove_file(&path);
+        return;
+    }
+
+    let queue: Arc<Mutex<Vec<PendingRequest>>> = Arc::new(Mutex::new(Vec::new()));
+    let queue_for_thread = queue.clone();
+
+    // Accept connections in the background, one thread per client
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    let queue = queue_for_thread.clone();
+                    thread::spawn(move || serve_connection(stream, queue));
+                }
+                Err(e) => eprintln!("IPC connection error: {}", e),
+            }
+        }
+    });
+
+    // Poll the request queue in the GTK main loop
+    let ctx = ctx.clone();
+    glib::timeout_add_local(Duration::from_millis(IPC_POLL_INTERVAL_MS), move || {
+        let pending: Vec<PendingRequest> = match queue.lock() {
+            Ok(mut q) => q.drain(..).collect(),
+            Err(_) => Vec::new(),
+        };
+
+        for PendingRequest { id, request, reply } in pending {
+            let result = handle_request(&ctx, request, &reply);
+            let _ = reply.send(response_line(id, result));
+        }
+
+        glib::ControlFlow::Continue
+    });
+
+    app.connect_shutdown(move |_| {
+        let _ = fs::remove_file(&path);
+    });
+}
+
+/// Reads requests from one client until it disconnects.
+///
+/// Responses and events are written by a companion thread draining the
+/// connection's outgoing channel, so events can arrive between responses.
+fn serve_connection(stream: UnixStream, queue: Arc<Mutex<Vec<PendingRequest>>>) {
+    let mut writer = match stream.try_clone() {
+        Ok(w) => w,
+        Err(e) => {
+            eprintln!("IPC connection error: {}", e);
+            return;
+        }
+    };
+
+    let (reply, outgoing) = channel::<String>();
+    thread::spawn(move || {
+        for line in outgoing {
+            if writeln!(writer, "{}", line).is_err() {
+                break;
+            }
+        }
+    });
+
+    for line in BufReader::new(stream).lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match parse_request(&line) {
+            Ok((id, request)) => {
+                if let Ok(mut q) = queue.lock() {
+                    q.push(PendingRequest {
+                        id,
+                        request,
+                        reply: reply.clone(),
+                    });
+                }
+            }
+            Err((id, e)) => {
+                let _ = reply.send(response_line(id, Err(e)));
+            }
+        }
+    }
+}
+
+/// Parses one request line.
+///
+/// # Returns
+/// * `Ok((id, request))` - The request and its optional correlation id
+/// * `Err((id, message))` - Why the line was rejected, with the id if readable
+fn parse_request(line: &str) -> Result<(Option<Value>, Request), (Option<Value>, String)> {
+    let mut value: Value =
+        serde_json::from_str(line).map_err(|e| (None, format!("Invalid JSON: {}", e)))?;
+
+    let id = value.as_object_mut().and_then(|obj| obj.remove("id"));
+
+    match serde_json::from_value(value) {
+        Ok(request) => Ok((id, request)),
+        Err(e) => Err((id, format!("Invalid request: {}", e))),
+    }
+}
+
+/// Formats a response line, echoing the request id if there was one.
+fn response_line(id: Option<Value>, result: Result<Map<String, Value>, String>) -> String {
+    let mut obj = match result {
+        Ok(mut fields) => {
+            fields.insert("ok".to_string(), Value::Bool(true));
+            fields
+        }
+        Err(e) => {
+            let mut fields = Map::new();
+            fields.insert("ok".to_string(), Value::Bool(false));
+            fields.insert("error".to_string(), Value::String(e));
+            fields
+        }
+    };
+
+    if let Some(id) = id {
+        obj.insert("id".to_string(), id);
+    }
+
+    Value::Object(obj).to_string()
+}
+
+/// Executes a request on the GTK thread.
+///
+/// # Returns
+/// Extra response fields on success, or an error message
+fn handle_request(
+    ctx: &AppContext,
+    request: Request,
+    reply: &Sender<String>,
+) -> Result<Map<String, Value>, String> {
+    match request {
+        Request::SetMode { mode } => {
+            let mode: DisplayMode = mode.parse()?;
+            ctx.clock_display.borrow().set_mode(mode);
+        }
+        Request::StartTimer { duration } => {
+            let duration = parse_duration(&duration)?;
+            ctx.clock_display.borrow().start_timer(duration);
+        }
+        Request::ToggleRunning => ctx.clock_display.borrow().toggle_running(),
+        Request::Reset => ctx.clock_display.borrow().reset_mode(),
+        Request::ShowMessage { text } => ctx.clock_display.borrow().show_message(&text),
+        Request::SetColors { colors } => {
+            if let Some(unknown) = colors.keys().find(|k| !COLOR_KEYS.contains(&k.as_str())) {
+                return Err(format!("Unknown color '{}'", unknown));
+            }
+            // References resolve against the colors in use
+            for value in colors.values() {
+                ctx.config.borrow().colors.eval(value)?;
+            }
+
+            let mut source = ctx.source.borrow_mut();
+            for (key, value) in colors {
+                source.set("colors", &key, toml::Value::String(value));
+            }
+            ctx.request_reload();
+        }
+        Request::SetTheme { name } => crate::actions::set_theme_override(ctx, &name)?,
+        Request::ReloadConfig => ctx.request_reload(),
+        Request::GetState => {
+            let display = ctx.clock_display.borrow();
+            let mut fields = Map::new();
+            fields.insert(
+                "state".to_string(),
+                json!({
+                    "mode": display.mode().as_str(),
+                    "running": display.is_running(),
+                    "reading": display.reading(),
+                    "showing_message": display.is_showing_message(),
+                    "theme": ctx.config.borrow().theme.name,
+                    "colors": ctx.config.borrow().colors,
+                }),
+            );
+            return Ok(fields);
+        }
+        Request::Subscribe { events } => {
+            if let Some(unknown) = events.iter().find(|e| !EVENT_NAMES.contains(&e.as_str())) {
+                return Err(format!(
+                    "Unknown event '{}' (expected {})",
+                    unknown,
+                    EVENT_NAMES.join(", ")
+                ));
+            }
+            ctx.events.subscribe(events, reply.clone());
+        }
+    }
+
+    Ok(Map::new())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ColorConfig;
+
+    #[test]
+    fn test_parse_request_commands() {
+        let (_, request) = parse_request(r#"{"command": "set-mode", "mode": "timer"}"#).unwrap();
+        assert_eq!(
+            request,
+            Request::SetMode {
+                mode: "timer".to_string()
+            }
+        );
+
+        let (_, request) = parse_request(r#"{"command": "toggle-running"}"#).unwrap();
+        assert_eq!(request, Request::ToggleRunning);
+
+        let (_, request) =
+            parse_request(r##"{"command": "set-colors", "colors": {"clock_bg": "#000000"}}"##)
+                .unwrap();
+        let Request::SetColors { colors } = request else {
+            panic!("expected set-colors");
+        };
+        assert_eq!(colors["clock_bg"], "#000000");
+
+        let (_, request) = parse_request(r#"{"command": "set-theme", "name": "nord"}"#).unwrap();
+        assert_eq!(
+            request,
+            Request::SetTheme {
+                name: "nord".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_request_subscribe_defaults_to_all() {
+        let (_, request) = parse_request(r#"{"command": "subscribe"}"#).unwrap();
+        assert_eq!(request, Request::Subscribe { events: Vec::new() });
+    }
+
+    #[test]
+    fn test_parse_request_keeps_id() {
+        let (id, request) = parse_request(r#"{"id": 7, "command": "get-state"}"#).unwrap();
+        assert_eq!(id, Some(json!(7)));
+        assert_eq!(request, Request::GetState);
+    }
+
+    #[test]
+    fn test_parse_request_errors() {
+        assert!(parse_request("not json").is_err());
+
+        let (id, _) = parse_request(r#"{"id": "a", "command": "explode"}"#).unwrap_err();
+        assert_eq!(id, Some(json!("a")));
+
+        assert!(parse_request(r#"{"command": "set-mode"}"#).is_err());
+    }
+
+    #[test]
+    fn test_response_line() {
+        let ok: Value =
+            serde_json::from_str(&response_line(Some(json!(1)), Ok(Map::new()))).unwrap();
+        assert_eq!(ok, json!({"ok": true, "id": 1}));
+
+        let err: Value =
+            serde_json::from_str(&response_line(None, Err("nope".to_string()))).unwrap();
+        assert_eq!(err, json!({"ok": false, "error": "nope"}));
+    }
+
+    #[test]
+    fn test_event_serialization() {
+        let line = serde_json::to_string(&Event::Tick {
+            mode: "clock".to_string(),
+            reading: "12:34:56".to_string(),
+        })
+        .unwrap();
+        let value: Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(
+            value,
+            json!({"event": "tick", "mode": "clock", "reading": "12:34:56"})
+        );
+
+        let value = serde_json::to_value(Event::ConfigReloaded).unwrap();
+        assert_eq!(value, json!({"event": "config-reloaded"}));
+    }
+
+    #[test]
+    fn test_event_bus_filters_and_drops_closed() {
+        let bus = EventBus::default();
+        let (alarm_tx, alarm_rx) = channel();
+        let (all_tx, all_rx) = channel();
+        let (closed_tx, closed_rx) = channel::<String>();
+        drop(closed_rx);
+
+        bus.subscribe(vec!["alarm".to_string()], alarm_tx);
+        bus.subscribe(Vec::new(), all_tx);
+        bus.subscribe(Vec::new(), closed_tx);
+
+        bus.publish(&Event::ConfigReloaded);
+        bus.publish(&Event::Alarm);
+
+        assert_eq!(alarm_rx.try_iter().count(), 1);
+        assert_eq!(all_rx.try_iter().count(), 2);
+        assert_eq!(bus.subscribers.borrow().len(), 2);
+    }
+
+    #[test]
+    fn test_color_keys() {
+        // Every color key is a string field of [colors], as `set-colors` assumes
+        let colors = ColorConfig::default();
+        for key in COLOR_KEYS {
+            assert!(colors.get(key).is_some(), "{}", key);
+        }
+        assert!(colors.get("schedule").is_none());
+    }
+
+    #[test]
+    fn test_remove_stale_socket() {
+        let dir = std::env::temp_dir().join("chronomatrix_test_ipc_socket");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(SOCKET_NAME);
+        let _ = fs::remove_file(&path);
+        assert!(remove_stale_socket(&path).is_ok());
+
+        // A live socket is kept
+        let listener = UnixListener::bind(&path).unwrap();
+        assert!(remove_stale_socket(&path).is_err());
+        assert!(path.exists());
+
+        // Once nobody listens, it is stale and removed
+        drop(listener);
+        assert!(remove_stale_socket(&path).is_ok());
+        assert!(!path.exists());
+
+        // The directory is private only once closed to others
+        let uid = fs::metadata(&dir).unwrap().uid();
+        fs::set_permissions(&dir, fs::Permissions::from_mode(0o755)).unwrap();
+        assert!(check_private_dir(&dir, uid).is_err());
+        fs::set_permissions(&dir, fs::Permissions::from_mode(0o700)).unwrap();
+        assert!(check_private_dir(&dir, uid).is_ok());
+        assert!(check_private_dir(&dir, uid + 1).is_err());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
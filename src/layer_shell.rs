@@ -0,0 +1,148 @@
+//! Widget mode on Wayland through the layer-shell protocol.
+//!
+//! Wayland clients can't position themselves or stay below other windows,
+//! so widget mode turns the window into a layer surface on the compositor's
+//! bottom layer using `gtk4-layer-shell`. Only the handful of functions used
+//! here are bound, and the library is loaded at runtime: without it widget
+//! mode falls back to a normal window, as on an unsupported compositor.
+//!
+//! Requires a compositor implementing `wlr-layer-shell` (Sway, Hyprland,
+//! KDE Plasma, niri, ...); GNOME doesn't, and the window stays a normal one.
+
+use glib::translate::{IntoGlib, ToGlibPtr};
+use gtk4::prelude::*;
+use gtk4::{ApplicationWindow, glib};
+use libloading::Library;
+use std::ffi::{CString, c_char, c_int};
+use std::sync::OnceLock;
+
+use crate::config::Anchor;
+
+type GtkWindow = gtk4::ffi::GtkWindow;
+
+const LAYER_BOTTOM: c_int = 1;
+const EDGE_LEFT: c_int = 0;
+const EDGE_RIGHT: c_int = 1;
+const EDGE_TOP: c_int = 2;
+const EDGE_BOTTOM: c_int = 3;
+const KEYBOARD_MODE_NONE: c_int = 0;
+
+/// Shared library providing the `gtk_layer_*` functions
+const LIBRARY: &str = "libgtk4-layer-shell.so.0";
+
+/// The `gtk4-layer-shell` functions used here.
+struct LayerShell {
+    is_supported: unsafe extern "C" fn() -> glib::ffi::gboolean,
+    init_for_window: unsafe extern "C" fn(*mut GtkWindow),
+    is_layer_window: unsafe extern "C" fn(*mut GtkWindow) -> glib::ffi::gboolean,
+    set_namespace: unsafe extern "C" fn(*mut GtkWindow, *const c_char),
+    set_layer: unsafe extern "C" fn(*mut GtkWindow, c_int),
+    set_keyboard_mode: unsafe extern "C" fn(*mut GtkWindow, c_int),
+    set_anchor: unsafe extern "C" fn(*mut GtkWindow, c_int, glib::ffi::gboolean),
+    set_margin: unsafe extern "C" fn(*mut GtkWindow, c_int, c_int),
+    /// Keeps the functions above loaded
+    _library: Library,
+}
+
+impl LayerShell {
+    fn load() -> Result<Self, libloading::Error> {
+        // SAFETY: the library has no initialization side effects, and each
+        // symbol is looked up with the signature from its header
+        unsafe {
+            let library = Library::new(LIBRARY)?;
+            Ok(Self {
+                is_supported: *library.get(b"gtk_layer_is_supported\0")?,
+                init_for_window: *library.get(b"gtk_layer_init_for_window\0")?,
+                is_layer_window: *library.get(b"gtk_layer_is_layer_window\0")?,
+                set_namespace: *library.get(b"gtk_layer_set_namespace\0")?,
+                set_layer: *library.get(b"gtk_layer_set_layer\0")?,
+                set_keyboard_mode: *library.get(b"gtk_layer_set_keyboard_mode\0")?,
+                set_anchor: *library.get(b"gtk_layer_set_anchor\0")?,
+                set_margin: *library.get(b"gtk_layer_set_margin\0")?,
+                _library: library,
+            })
+        }
+    }
+}
+
+/// Returns the library's functions, loading it on first use.
+///
+/// # Returns
+/// `None` if `gtk4-layer-shell` isn't installed, which is reported once
+fn layer_shell() -> Option<&'static LayerShell> {
+    static LAYER_SHELL: OnceLock<Option<LayerShell>> = OnceLock::new();
+    LAYER_SHELL
+        .get_or_init(|| {
+            LayerShell::load()
+                .map_err(|e| eprintln!("Warning: Could not load {}: {}", LIBRARY, e))
+                .ok()
+        })
+        .as_ref()
+}
+
+fn raw(window: &ApplicationWindow) -> *mut GtkWindow {
+    window.upcast_ref::<gtk4::Window>().to_glib_none().0
+}
+
+/// Turns the window into a layer surface below normal windows.
+///
+/// Must be called before the window is realized.
+///
+/// # Returns
+/// `false` if the compositor doesn't support layer-shell or the library
+/// isn't installed
+pub fn init(window: &ApplicationWindow) -> bool {
+    let Some(layer) = layer_shell() else {
+        return false;
+    };
+    // SAFETY: the window pointer is valid for the duration of the calls
+    unsafe {
+        if (layer.is_supported)() == glib::ffi::GFALSE {
+            return false;
+        }
+        let window = raw(window);
+        let name_space = CString::new("chronomatrix").unwrap();
+        (layer.init_for_window)(window);
+        (layer.set_namespace)(window, name_space.as_ptr());
+        (layer.set_layer)(window, LAYER_BOTTOM);
+        (layer.set_keyboard_mode)(window, KEYBOARD_MODE_NONE);
+    }
+    true
+}
+
+/// Returns whether the window was turned into a layer surface by `init`.
+pub fn is_layer_window(window: &ApplicationWindow) -> bool {
+    let Some(layer) = layer_shell() else {
+        return false;
+    };
+    // SAFETY: the window pointer is valid for the duration of the call
+    unsafe { (layer.is_layer_window)(raw(window)) != glib::ffi::GFALSE }
+}
+
+/// Attaches a layer surface to the screen edges of an anchor.
+///
+/// # Arguments
+/// * `window` - A window set up with `init`
+/// * `anchor` - Where to place the window
+/// * `margin` - Distance from the attached edges in pixels
+pub fn set_anchor(window: &ApplicationWindow, anchor: Anchor, margin: i32) {
+    let Some(layer) = layer_shell() else {
+        return;
+    };
+    let edges = anchor.edges();
+    let sides = [
+        (EDGE_LEFT, edges.left),
+        (EDGE_RIGHT, edges.right),
+        (EDGE_TOP, edges.top),
+        (EDGE_BOTTOM, edges.bottom),
+    ];
+
+    // SAFETY: the window pointer is valid for the duration of the calls
+    unsafe {
+        let window = raw(window);
+        for (edge, attached) in sides {
+            (layer.set_anchor)(window, edge, attached.into_glib());
+            (layer.set_margin)(window, edge, if attached { margin } else { 0 });
+        }
+    }
+}
@@ -2,22 +2,52 @@
 //!
 //! This is the main application entry point. It handles:
 //! - GTK4 application initialization and window setup
-//! - Configuration loading and hot-reload watching
-//! - CSS styling with dynamic color injection
+//! - Configuration loading and hot-reload watching, keeping the last good
+//!   config when an edit introduces errors and showing them in a toast
+//! - CSS styling with dynamic color injection and an optional custom
+//!   stylesheet (see `style`)
+//! - Built-in and user color themes, switchable at runtime or following the
+//!   desktop's dark/light preference and accent color (see `theme`)
+//! - Window modes (normal, fullscreen, desktop widget), transparency, input
+//!   passthrough, edge resizing and saved geometry (see `window`)
 //! - Timer setup for clock updates every second
+//! - `app.*` actions and command-line handling for remote control, plus
+//!   keyboard shortcuts and a context menu (see `controls`)
+//! - A preferences dialog that edits the config files (see `preferences`)
+//! - The JSON control socket (see `ipc`)
 //!
 //! The application creates a frameless window containing a `ClockDisplay` widget
 //! that shows the current time as HH:MM:SS using 6 digits (each digit being
 //! a 6x4 grid of 24 analog clocks).
 
+mod actions;
 mod analog_clock;
+mod cli;
 mod clock_display;
+mod color;
 mod config;
+mod config_edit;
+mod controls;
+mod diagnostics;
 mod digit_display;
 mod digit_patterns;
+mod glyph_patterns;
+mod ipc;
+#[cfg(feature = "layer-shell")]
+mod layer_shell;
+mod mode;
+mod preferences;
+mod schedule;
+mod style;
+mod theme;
+mod window;
+mod window_state;
+#[cfg(feature = "x11")]
+mod x11;
 
 use gtk4::prelude::*;
-use gtk4::{Application, ApplicationWindow, CssProvider, gdk, glib};
+use gtk4::{Application, ApplicationWindow, gio, glib};
+use libadwaita as adw;
 use notify::{RecommendedWatcher, RecursiveMode, Watcher};
 use std::cell::RefCell;
 use std::collections::HashSet;
@@ -29,7 +59,11 @@ use std::thread;
 use std::time::Duration;
 
 use clock_display::ClockDisplay;
-use config::{Config, parse_hex_color};
+use config::{Config, ConfigSource, WindowMode};
+use diagnostics::Diagnostic;
+use ipc::{Event, EventBus};
+use style::Stylesheet;
+use window_state::WindowState;
 
 const APP_ID: &str = "com.github.chronomatrix";
 
@@ -54,19 +88,133 @@ struct ReloadState {
 /// Holds the file watcher and the set of directories currently being watched.
 /// Lives on the GTK thread so it can be mutated on reload.
 struct WatcherState {
-    watcher: RecommendedWatcher,
+    /// `None` if the watcher could not be created; reloads can still be requested
+    watcher: Option<RecommendedWatcher>,
     watched_dirs: HashSet<PathBuf>,
 }
 
+/// Shared handles to the running UI.
+///
+/// Cloned into timers, actions and the IPC dispatcher; every clone refers to
+/// the same window, config and display.
+#[derive(Clone)]
+struct AppContext {
+    window: ApplicationWindow,
+    /// Window mode the window was set up with, `Normal` if widget mode was
+    /// unavailable; entering or leaving widget mode only takes effect on the
+    /// next start
+    startup_mode: WindowMode,
+    /// Window content: the clock display, with config errors shown as toasts
+    toast_overlay: adw::ToastOverlay,
+    /// Toast reporting the current config errors, dismissed once they are fixed
+    error_toast: Rc<RefCell<Option<adw::Toast>>>,
+    config: Rc<RefCell<Config>>,
+    clock_display: Rc<RefCell<ClockDisplay>>,
+    /// The app's single CSS provider, reloaded in place with the config
+    stylesheet: Rc<Stylesheet>,
+    /// Config file and command-line/IPC overrides, re-read on every reload
+    source: Rc<RefCell<ConfigSource>>,
+    /// Fans events out to IPC subscribers
+    events: Rc<EventBus>,
+    /// Reload flag shared with the file watcher thread
+    reload_state: Arc<Mutex<ReloadState>>,
+}
+
+impl AppContext {
+    /// Schedules a config reload on the next poll of the reload flag.
+    fn request_reload(&self) {
+        if let Ok(mut state) = self.reload_state.lock() {
+            state.should_reload = true;
+        }
+    }
+
+    /// Shows config errors in a toast that stays until dismissed or fixed.
+    ///
+    /// Replaces the toast from a previous failed load, if any.
+    ///
+    /// # Arguments
+    /// * `diagnostics` - Problems from loading; only the errors are shown
+    /// * `fallback` - What the app does instead, e.g. "using defaults"
+    fn show_config_errors(&self, diagnostics: &[Diagnostic], fallback: &str) {
+        let errors: Vec<&Diagnostic> = diagnostics.iter().filter(|d| d.is_error()).collect();
+        let Some(first) = errors.first() else {
+            return;
+        };
+
+        let location = match (&first.file, first.position) {
+            (Some(file), Some((line, column))) => {
+                format!("{}:{}:{}: ", file_name(file), line, column)
+            }
+            (Some(file), None) => format!("{}: ", file_name(file)),
+            (None, _) => String::new(),
+        };
+        let more = match errors.len() {
+            1 => String::new(),
+            n => format!(" (+{} more)", n - 1),
+        };
+
+        let toast = adw::Toast::builder()
+            .title(format!(
+                "Config error, {}: {}{}{}",
+                fallback, location, first.message, more
+            ))
+            .use_markup(false)
+            .timeout(0)
+            .priority(adw::ToastPriority::High)
+            .build();
+
+        self.dismiss_config_errors();
+        self.toast_overlay.add_toast(toast.clone());
+        *self.error_toast.borrow_mut() = Some(toast);
+    }
+
+    /// Removes the config error toast, if one is showing.
+    fn dismiss_config_errors(&self) {
+        if let Some(toast) = self.error_toast.borrow_mut().take() {
+            toast.dismiss();
+        }
+    }
+}
+
+/// Returns the file name of a path for display, or the whole path if it has none.
+fn file_name(path: &std::path::Path) -> String {
+    path.file_name().map_or_else(
+        || path.display().to_string(),
+        |n| n.to_string_lossy().into_owned(),
+    )
+}
+
 /// Application entry point.
 ///
 /// Creates a GTK4 application and runs it. The application is initialized with
 /// the ID "com.github.chronomatrix" and connects the `build_ui` function to the
 /// activate signal, which is triggered when the application starts.
+///
+/// The application handles its own command line so that invoking it again
+/// while running forwards the options to the existing instance. Config
+/// options from the command line are kept in a `ConfigSource` shared with the
+/// UI, so they survive hot reloads.
 fn main() -> glib::ExitCode {
-    let app = Application::builder().application_id(APP_ID).build();
+    let app = Application::builder()
+        .application_id(APP_ID)
+        .flags(gio::ApplicationFlags::HANDLES_COMMAND_LINE)
+        .build();
 
-    app.connect_activate(build_ui);
+    let source = Rc::new(RefCell::new(ConfigSource::default()));
+
+    cli::register_options(&app);
+    app.connect_startup(|_| {
+        if let Err(e) = adw::init() {
+            eprintln!("Failed to initialize libadwaita: {}", e);
+        }
+    });
+    app.connect_activate(glib::clone!(
+        #[strong]
+        source,
+        move |app| build_ui(app, &source)
+    ));
+    app.connect_handle_local_options(cli::handle_local_options);
+    app.connect_command_line(move |app, cmdline| cli::handle_command_line(app, cmdline, &source));
 
     app.run()
 }
@@ -79,50 +227,147 @@ fn main() -> glib::ExitCode {
 /// - Applies CSS styling with colors from config
 /// - Sets up a 1-second timer for time updates
 /// - Configures file watching for hot-reload of config changes
+/// - Registers the `app.*` actions and opens the IPC socket
+///
+/// If the window already exists (a repeated activation), it is raised instead.
 ///
 /// # Arguments
 /// * `app` - The GTK application instance
-fn build_ui(app: &Application) {
+/// * `source` - Config file and overrides to load from
+fn build_ui(app: &Application, source: &Rc<RefCell<ConfigSource>>) {
+    if let Some(window) = app.active_window() {
+        window.present();
+        return;
+    }
+
     // Load configuration (with include support)
-    let load_result = Config::load_or_default();
+    source.borrow_mut().system = style::system_style();
+    let load_result = Config::load_or_default(&source.borrow());
+    for diagnostic in &load_result.diagnostics {
+        eprintln!("{}", diagnostic);
+    }
+    let diagnostics = load_result.diagnostics;
     let source_files = load_result.source_files;
+    let custom_css = load_result.custom_css;
     let config = Rc::new(RefCell::new(load_result.config));
 
     // Create the main window
     let window = ApplicationWindow::builder()
         .application(app)
         .title("Chronomatrix")
-        .default_width(1200)
-        .default_height(400)
         .decorated(false) // Remove title bar
         .build();
 
+    let state_path = WindowState::default_path();
+    let startup_mode = window::setup(&window, &config, &WindowState::load(&state_path));
+
     // Load CSS for styling
-    load_css(&config.borrow());
+    let stylesheet = Rc::new(Stylesheet::new());
+    stylesheet.apply(&config.borrow(), custom_css.as_deref());
 
-    // Create the clock display
+    // Create the clock display, and style the window with the colors it
+    // starts with if they are scheduled
     let clock_display = Rc::new(RefCell::new(ClockDisplay::new(&config.borrow())));
+    stylesheet.set_colors(&clock_display.borrow().current_colors());
+
+    // Set initial time, or scroll the configured startup message
+    if config.borrow().marquee.message.is_empty() {
+        clock_display.borrow().update_time();
+    } else {
+        clock_display
+            .borrow()
+            .show_message(&config.borrow().marquee.message);
+    }
 
-    // Set initial time
-    clock_display.borrow().update_time();
+    // Add the clock display to the window, inside an overlay for error toasts
+    // and a handle that moves the window when dragged
+    let toast_overlay = adw::ToastOverlay::new();
+    toast_overlay.set_child(Some(clock_display.borrow().widget()));
+    let window_handle = gtk4::WindowHandle::new();
+    window_handle.set_child(Some(&toast_overlay));
+    window.set_child(Some(&window_handle));
+
+    // Remember the window geometry for the next launch, whether the window
+    // is closed or the app quits through an action
+    window.connect_close_request(glib::clone!(
+        #[strong]
+        config,
+        #[strong]
+        state_path,
+        move |window| {
+            window::save_state(window, config.borrow().window.mode, &state_path);
+            glib::Propagation::Proceed
+        }
+    ));
+    app.connect_shutdown(glib::clone!(
+        #[weak]
+        window,
+        #[strong]
+        config,
+        move |_| {
+            if window.is_visible() {
+                window::save_state(&window, config.borrow().window.mode, &state_path);
+            }
+        }
+    ));
+
+    let ctx = AppContext {
+        window: window.clone(),
+        startup_mode,
+        toast_overlay,
+        error_toast: Rc::new(RefCell::new(None)),
+        config,
+        clock_display,
+        stylesheet,
+        source: source.clone(),
+        events: Rc::new(EventBus::default()),
+        // Create a state struct to signal config reload with timestamp for debouncing
+        reload_state: Arc::new(Mutex::new(ReloadState {
+            should_reload: false,
+            last_reload: std::time::Instant::now(),
+        })),
+    };
+
+    ctx.show_config_errors(&diagnostics, "using defaults");
 
     // Setup timer to update every second
-    let clock_display_clone = clock_display.clone();
+    let tick_ctx = ctx.clone();
     glib::timeout_add_local(Duration::from_secs(1), move || {
-        clock_display_clone.borrow().update_time();
+        let display = tick_ctx.clock_display.borrow();
+        if display.update_time() {
+            tick_ctx.events.publish(&Event::Alarm);
+        }
+        if !tick_ctx.config.borrow().colors.schedule.is_empty() {
+            tick_ctx.stylesheet.set_colors(&display.current_colors());
+        }
+        tick_ctx.events.publish(&Event::Tick {
+            mode: display.mode().to_string(),
+            reading: display.reading(),
+        });
         glib::ControlFlow::Continue
     });
 
-    // Add the clock display to the window
-    window.set_child(Some(clock_display.borrow().widget()));
-
     // Setup config file watcher (watches all source files including includes)
-    setup_config_watcher(
-        window.clone(),
-        config.clone(),
-        clock_display.clone(),
-        source_files,
-    );
+    setup_config_watcher(ctx.clone(), source_files);
+
+    // Follow the desktop's dark/light preference and accent color; the
+    // reload cross-fades to the new colors without rebuilding the clocks
+    style::connect_system_style_changed(glib::clone!(
+        #[strong]
+        ctx,
+        move || {
+            ctx.source.borrow_mut().system = style::system_style();
+            if ctx.config.borrow().theme.follow_system {
+                ctx.request_reload();
+            }
+        }
+    ));
+
+    // Expose remote control through actions and the IPC socket
+    actions::register(app, &ctx);
+    controls::set_shortcuts(app, &ctx.config.borrow().shortcuts);
+    controls::setup_context_menu(&ctx.toast_overlay);
+    ipc::start(app, &ctx);
 
     // Present the window
     window.present();
@@ -149,17 +394,13 @@ fn parent_dirs(files: &HashSet<PathBuf>) -> HashSet<PathBuf> {
 /// Watches all source files (main config + includes). The watcher lives on the GTK
 /// thread so it can be mutated on reload to watch/unwatch directories as includes change.
 ///
+/// Reloads can also be requested through `AppContext::request_reload`, which
+/// sets the same flag the watcher thread does.
+///
 /// # Arguments
-/// * `window` - The application window to update after reload
-/// * `config` - Shared reference to the config that will be updated
-/// * `clock_display` - Shared reference to the clock display to be recreated
+/// * `ctx` - Shared handles to the window, config and clock display to update
 /// * `source_files` - Initial set of config source files to watch
-fn setup_config_watcher(
-    window: ApplicationWindow,
-    config: Rc<RefCell<Config>>,
-    clock_display: Rc<RefCell<ClockDisplay>>,
-    source_files: Vec<PathBuf>,
-) {
+fn setup_config_watcher(ctx: AppContext, source_files: Vec<PathBuf>) {
     // Resolve all source files and compute directories to watch
     let resolved_files = resolve_source_files(&source_files);
     let dirs_to_watch = parent_dirs(&resolved_files);
@@ -169,20 +410,22 @@ fn setup_config_watcher(
 
     // Create watcher on GTK thread
     let mut watcher = match notify::recommended_watcher(tx) {
-        Ok(w) => w,
+        Ok(w) => Some(w),
         Err(e) => {
             eprintln!("Failed to create file watcher: {}", e);
-            return;
+            None
         }
     };
 
     // Watch all directories containing source files
     let mut watched_dirs = HashSet::new();
-    for dir in &dirs_to_watch {
-        if let Err(e) = watcher.watch(dir, RecursiveMode::NonRecursive) {
-            eprintln!("Failed to watch directory {:?}: {}", dir, e);
-        } else {
-            watched_dirs.insert(dir.clone());
+    if let Some(watcher) = watcher.as_mut() {
+        for dir in &dirs_to_watch {
+            if let Err(e) = watcher.watch(dir, RecursiveMode::NonRecursive) {
+                eprintln!("Failed to watch directory {:?}: {}", dir, e);
+            } else {
+                watched_dirs.insert(dir.clone());
+            }
         }
     }
 
@@ -195,15 +438,15 @@ fn setup_config_watcher(
     let watched_files: Arc<Mutex<HashSet<PathBuf>>> = Arc::new(Mutex::new(resolved_files));
     let watched_files_for_thread = watched_files.clone();
 
-    // Create a state struct to signal config reload with timestamp for debouncing
-    let reload_state = Arc::new(Mutex::new(ReloadState {
-        should_reload: false,
-        last_reload: std::time::Instant::now(),
-    }));
-    let reload_state_clone = reload_state.clone();
+    let reload_state_clone = ctx.reload_state.clone();
 
     // Spawn background thread to receive events and set the reload flag
+    let has_watcher = watcher_state.borrow().watcher.is_some();
     thread::spawn(move || {
+        if !has_watcher {
+            return;
+        }
+
         loop {
             match rx.recv() {
                 Ok(Ok(event)) => {
@@ -233,17 +476,12 @@ fn setup_config_watcher(
 
     // Poll the reload flag in the GTK main loop
     glib::timeout_add_local(Duration::from_millis(CONFIG_POLL_INTERVAL_MS), move || {
-        if let Ok(mut state) = reload_state.lock()
-            && state.should_reload
-        {
-            state.should_reload = false;
-            reload_config(
-                &window,
-                &config,
-                &clock_display,
-                &watcher_state,
-                &watched_files,
-            );
+        let should_reload = match ctx.reload_state.lock() {
+            Ok(mut state) => std::mem::take(&mut state.should_reload),
+            Err(_) => false,
+        };
+        if should_reload {
+            reload_config(&ctx, &watcher_state, &watched_files);
         }
         glib::ControlFlow::Continue
     });
@@ -263,10 +501,10 @@ fn is_watched_file_event(
 
     event.paths.iter().any(|event_path| {
         // Try exact match via canonicalization
-        if let Ok(canonical) = std::fs::canonicalize(event_path) {
-            if files.contains(&canonical) {
-                return true;
-            }
+        if let Ok(canonical) = std::fs::canonicalize(event_path)
+            && files.contains(&canonical)
+        {
+            return true;
         }
         // Fallback: match by filename against any watched file
         if let Some(event_name) = event_path.file_name() {
@@ -276,48 +514,110 @@ fn is_watched_file_event(
     })
 }
 
-/// Reloads the configuration, recreates the clock display, and updates watched files/dirs.
+/// Reloads the configuration, updates the clock display, and updates watched files/dirs.
+///
+/// The display is only rebuilt for layout changes (see
+/// `ClockDisplay::requires_rebuild`); otherwise it is restyled in place.
+///
+/// If the new configuration has errors, they are reported on stderr and in a
+/// toast, and the current configuration stays in place. Publishes `Event::ConfigReloaded` to IPC
+/// subscribers when a new configuration was applied.
 fn reload_config(
-    window: &ApplicationWindow,
-    config: &Rc<RefCell<Config>>,
-    clock_display: &Rc<RefCell<ClockDisplay>>,
+    ctx: &AppContext,
     watcher_state: &Rc<RefCell<WatcherState>>,
     watched_files: &Arc<Mutex<HashSet<PathBuf>>>,
 ) {
-    // Load new config (with includes)
-    let load_result = Config::load_or_default();
+    // Load new config (with includes and runtime overrides)
+    let load_result = {
+        let source = ctx.source.borrow();
+        Config::load(&source.path(), &source.overrides, &source.system)
+    };
+
+    let load_result = match load_result {
+        Ok(result) => result,
+        Err(e) => {
+            eprintln!("{}", e);
+            eprintln!("Keeping the previous configuration");
+            ctx.show_config_errors(&e.diagnostics, "keeping the previous config");
+            // Keep watching the files that were read, so a fix is picked up
+            update_watched_files(watcher_state, watched_files, &e.source_files);
+            return;
+        }
+    };
+    for diagnostic in &load_result.diagnostics {
+        eprintln!("{}", diagnostic);
+    }
+    ctx.dismiss_config_errors();
     let new_config = load_result.config;
 
+    window::apply(
+        &ctx.window,
+        ctx.startup_mode,
+        &ctx.config.borrow().window,
+        &new_config.window,
+    );
+
     // Reload CSS
-    load_css(&new_config);
+    ctx.stylesheet
+        .apply(&new_config, load_result.custom_css.as_deref());
 
     // Store the new config
-    *config.borrow_mut() = new_config.clone();
+    let old_config = ctx.config.replace(new_config.clone());
+
+    if old_config.shortcuts != new_config.shortcuts
+        && let Some(app) = ctx.window.application()
+    {
+        controls::set_shortcuts(&app, &new_config.shortcuts);
+    }
+
+    if ClockDisplay::requires_rebuild(&old_config, &new_config) {
+        // Recreate the clock display with new config, keeping any running timer
+        let new_clock_display = ClockDisplay::new(&new_config);
+        new_clock_display.inherit_state(&ctx.clock_display.borrow());
+        new_clock_display.update_time_immediate();
+        ctx.toast_overlay
+            .set_child(Some(new_clock_display.widget()));
+        *ctx.clock_display.borrow_mut() = new_clock_display;
+    } else {
+        // Restyle the existing clocks, keeping their hand positions
+        ctx.clock_display.borrow().apply_config(&new_config);
+    }
+    ctx.stylesheet
+        .set_colors(&ctx.clock_display.borrow().current_colors());
+
+    update_watched_files(watcher_state, watched_files, &load_result.source_files);
 
-    // Recreate the clock display with new config
-    let new_clock_display = ClockDisplay::new(&new_config);
-    new_clock_display.update_time_immediate();
-    window.set_child(Some(new_clock_display.widget()));
-    *clock_display.borrow_mut() = new_clock_display;
+    ctx.events.publish(&Event::ConfigReloaded);
+}
 
-    // Update watched files and directories
-    let new_resolved = resolve_source_files(&load_result.source_files);
+/// Points the file watcher at a new set of config source files.
+///
+/// Watches directories that became necessary and unwatches those that no
+/// longer contain any source file.
+fn update_watched_files(
+    watcher_state: &Rc<RefCell<WatcherState>>,
+    watched_files: &Arc<Mutex<HashSet<PathBuf>>>,
+    source_files: &[PathBuf],
+) {
+    let new_resolved = resolve_source_files(source_files);
     let new_dirs = parent_dirs(&new_resolved);
 
     let mut ws = watcher_state.borrow_mut();
     let old_dirs = ws.watched_dirs.clone();
 
-    // Unwatch directories that are no longer needed
-    for dir in old_dirs.difference(&new_dirs) {
-        if let Err(e) = ws.watcher.unwatch(dir) {
-            eprintln!("Failed to unwatch {:?}: {}", dir, e);
+    if let Some(watcher) = ws.watcher.as_mut() {
+        // Unwatch directories that are no longer needed
+        for dir in old_dirs.difference(&new_dirs) {
+            if let Err(e) = watcher.unwatch(dir) {
+                eprintln!("Failed to unwatch {:?}: {}", dir, e);
+            }
         }
-    }
 
-    // Watch new directories
-    for dir in new_dirs.difference(&old_dirs) {
-        if let Err(e) = ws.watcher.watch(dir, RecursiveMode::NonRecursive) {
-            eprintln!("Failed to watch {:?}: {}", dir, e);
+        // Watch new directories
+        for dir in new_dirs.difference(&old_dirs) {
+            if let Err(e) = watcher.watch(dir, RecursiveMode::NonRecursive) {
+                eprintln!("Failed to watch {:?}: {}", dir, e);
+            }
         }
     }
 
@@ -328,77 +628,3 @@ fn reload_config(
         *files = new_resolved;
     }
 }
-
-/// Loads and applies CSS styling based on configuration.
-///
-/// Generates CSS rules dynamically from config colors and applies them to
-/// the GTK display. This handles:
-/// - Window background color with opacity
-/// - Display container styling (background, border, padding, border-radius)
-///
-/// The window opacity setting from config is applied to the background color's
-/// alpha channel, allowing transparent backgrounds while keeping UI elements opaque.
-///
-/// # Arguments
-/// * `config` - The configuration containing color and opacity settings
-fn load_css(config: &Config) {
-    let provider = CssProvider::new();
-
-    // Parse background color and apply window opacity to it
-    let bg_color = &config.colors.window_background;
-    let (r, g, b, a) = parse_hex_color(bg_color);
-    // Multiply alpha by the opacity setting to control background transparency
-    let final_alpha = a * config.window.opacity;
-    let bg_rgba = format!(
-        "rgba({}, {}, {}, {})",
-        (r * 255.0) as u8,
-        (g * 255.0) as u8,
-        (b * 255.0) as u8,
-        final_alpha
-    );
-
-    // Parse display colors
-    let display_bg = &config.colors.display_bg;
-    let (dr, dg, db, da) = parse_hex_color(display_bg);
-    let display_rgba = format!(
-        "rgba({}, {}, {}, {})",
-        (dr * 255.0) as u8,
-        (dg * 255.0) as u8,
-        (db * 255.0) as u8,
-        da
-    );
-
-    let display_border = &config.colors.display_border;
-    let (border_r, border_g, border_b, border_a) = parse_hex_color(display_border);
-    let border_rgba = format!(
-        "rgba({}, {}, {}, {})",
-        (border_r * 255.0) as u8,
-        (border_g * 255.0) as u8,
-        (border_b * 255.0) as u8,
-        border_a
-    );
-
-    let css = format!(
-        r#"
-        window {{
-            background-color: {bg_rgba};
-            background: {bg_rgba};
-        }}
-
-        .clock-display {{
-            padding: 40px;
-            background: {display_rgba};
-            border-radius: 20px;
-            border: 1px solid {border_rgba};
-        }}
-        "#
-    );
-
-    provider.load_from_string(&css);
-
-    gtk4::style_context_add_provider_for_display(
-        &gdk::Display::default().expect("Could not connect to a display."),
-        &provider,
-        gtk4::STYLE_PROVIDER_PRIORITY_APPLICATION,
-    );
-}
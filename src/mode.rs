@@ -0,0 +1,456 @@
+//! Display modes: wall clock, countdown timer, stopwatch and ambient.
+//!
+//! In clock mode the digits show the wall-clock time (`WallClock`), in the
+//! local timezone or a configured one, as 24- or 12-hour. Timer mode counts down
+//! from a duration and stopwatch mode counts up; both can be paused and
+//! resumed. Either way the display shows HH:MM:SS, so this module only
+//! produces the six digits and leaves rendering to `ClockDisplay`.
+//!
+//! Ambient mode turns every analog clock into an ordinary clock showing the
+//! wall-clock time, forming the digits only for part of each cycle (see
+//! `ambient_shows_digits` and `analog_angles`).
+
+use chrono::{DateTime, Local, NaiveTime, Timelike, Utc};
+use chrono_tz::Tz;
+use std::time::{Duration, Instant};
+
+use crate::config::{AmbientConfig, DisplayMode, TimeFormat, parse_timezone};
+
+/// Countdown used when timer mode is selected without a duration (5 minutes)
+const DEFAULT_TIMER_SECS: u64 = 5 * 60;
+
+/// Largest value the HH:MM:SS display can show
+const MAX_DISPLAY_SECS: u64 = 99 * 3600 + 59 * 60 + 59;
+
+/// How the wall clock reads the time in clock mode.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct WallClock {
+    format: TimeFormat,
+    /// `None` for the system's local timezone
+    timezone: Option<Tz>,
+}
+
+impl WallClock {
+    /// Creates a wall clock.
+    ///
+    /// # Arguments
+    /// * `format` - 24- or 12-hour display
+    /// * `timezone` - IANA timezone name, or empty for local time
+    ///
+    /// # Returns
+    /// * `Ok(WallClock)` - The configured wall clock
+    /// * `Err(String)` - The timezone name is not known
+    pub fn new(format: TimeFormat, timezone: &str) -> Result<Self, String> {
+        Ok(WallClock {
+            format,
+            timezone: parse_timezone(timezone)?,
+        })
+    }
+
+    /// Returns the six HHMMSS digits for an instant.
+    pub fn digits(&self, now: DateTime<Utc>) -> String {
+        let pattern = match self.format {
+            TimeFormat::TwentyFourHour => "%H%M%S",
+            TimeFormat::TwelveHour => "%I%M%S",
+        };
+
+        match self.timezone {
+            Some(tz) => now.with_timezone(&tz).format(pattern).to_string(),
+            None => now.with_timezone(&Local).format(pattern).to_string(),
+        }
+    }
+
+    /// Returns the time of day in this clock's timezone, for color schedules.
+    pub fn time_of_day(&self, now: DateTime<Utc>) -> NaiveTime {
+        match self.timezone {
+            Some(tz) => now.with_timezone(&tz).time(),
+            None => now.with_timezone(&Local).time(),
+        }
+    }
+}
+
+/// Current mode plus the run state of the timer and stopwatch.
+#[derive(Debug, Clone)]
+pub struct ModeState {
+    mode: DisplayMode,
+    /// Length of the countdown in timer mode
+    timer_duration: Duration,
+    /// Time counted before the most recent start
+    accumulated: Duration,
+    /// When counting last resumed, or `None` while paused
+    started_at: Option<Instant>,
+}
+
+impl Default for ModeState {
+    fn default() -> Self {
+        ModeState {
+            mode: DisplayMode::Clock,
+            timer_duration: Duration::from_secs(DEFAULT_TIMER_SECS),
+            accumulated: Duration::ZERO,
+            started_at: None,
+        }
+    }
+}
+
+impl ModeState {
+    /// Returns the active display mode.
+    pub fn mode(&self) -> DisplayMode {
+        self.mode
+    }
+
+    /// Switches to a mode, stopped and reset to zero.
+    ///
+    /// A timer keeps the duration it was last started with.
+    pub fn set_mode(&mut self, mode: DisplayMode) {
+        self.mode = mode;
+        self.reset();
+    }
+
+    /// Switches to timer mode and starts counting down immediately.
+    ///
+    /// # Arguments
+    /// * `duration` - Length of the countdown
+    /// * `now` - The current instant
+    pub fn start_timer(&mut self, duration: Duration, now: Instant) {
+        self.mode = DisplayMode::Timer;
+        self.timer_duration = duration;
+        self.accumulated = Duration::ZERO;
+        self.started_at = Some(now);
+    }
+
+    /// Starts or pauses the timer or stopwatch. Has no effect in clock and
+    /// ambient mode.
+    ///
+    /// Starting a timer that has already run out restarts it from the top.
+    pub fn toggle_running(&mut self, now: Instant) {
+        if matches!(self.mode, DisplayMode::Clock | DisplayMode::Ambient) {
+            return;
+        }
+
+        if let Some(started_at) = self.started_at.take() {
+            self.accumulated += now.saturating_duration_since(started_at);
+        } else {
+            if self.mode == DisplayMode::Timer && self.accumulated >= self.timer_duration {
+                self.accumulated = Duration::ZERO;
+            }
+            self.started_at = Some(now);
+        }
+    }
+
+    /// Stops counting and returns to zero (or the full countdown).
+    pub fn reset(&mut self) {
+        self.accumulated = Duration::ZERO;
+        self.started_at = None;
+    }
+
+    /// Returns whether the timer or stopwatch is counting.
+    pub fn is_running(&self) -> bool {
+        self.started_at.is_some()
+    }
+
+    /// Returns the total time counted so far.
+    fn elapsed(&self, now: Instant) -> Duration {
+        self.accumulated
+            + self
+                .started_at
+                .map_or(Duration::ZERO, |s| now.saturating_duration_since(s))
+    }
+
+    /// Stops a running timer that has reached zero.
+    ///
+    /// # Returns
+    /// `true` exactly once, on the call that notices the countdown ran out
+    pub fn check_finished(&mut self, now: Instant) -> bool {
+        if self.mode != DisplayMode::Timer || !self.is_running() {
+            return false;
+        }
+
+        if self.elapsed(now) >= self.timer_duration {
+            self.accumulated = self.timer_duration;
+            self.started_at = None;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns the six HHMMSS digits to show, or `None` for the wall-clock
+    /// time in clock and ambient mode.
+    ///
+    /// Timers round up so a fresh 5 minute timer reads 00:05:00 rather than
+    /// 00:04:59. Values beyond 99:59:59 are clamped.
+    pub fn digits(&self, now: Instant) -> Option<String> {
+        let secs = match self.mode {
+            DisplayMode::Clock | DisplayMode::Ambient => return None,
+            DisplayMode::Timer => {
+                let remaining = self.timer_duration.saturating_sub(self.elapsed(now));
+                remaining.as_millis().div_ceil(1000) as u64
+            }
+            DisplayMode::Stopwatch => self.elapsed(now).as_secs(),
+        };
+
+        Some(format_hms(secs))
+    }
+}
+
+/// Returns whether ambient mode forms the digits at a time of day, rather
+/// than showing the time on every clock's hands.
+///
+/// Each cycle starts with `digital_secs` of digits followed by
+/// `analog_secs` of hands. Cycles are counted from midnight, so with the
+/// default 60 second cycle the digits show at the start of every minute.
+pub fn ambient_shows_digits(time: NaiveTime, ambient: &AmbientConfig) -> bool {
+    let cycle = ambient.digital_secs + ambient.analog_secs;
+    if cycle == 0 {
+        return true;
+    }
+    u64::from(time.num_seconds_from_midnight()) % cycle < ambient.digital_secs
+}
+
+/// Returns the hour and minute hand angles of an ordinary analog clock
+/// showing a time of day, in degrees clockwise from 12 o'clock.
+///
+/// The hour hand moves on with the minutes and the minute hand with the
+/// seconds, each in whole degrees.
+pub fn analog_angles(time: NaiveTime) -> (i32, i32) {
+    let minutes = (time.hour() % 12) * 60 + time.minute();
+    let hour = minutes / 2;
+    let minute = time.minute() * 6 + time.second() / 10;
+    (hour as i32, minute as i32)
+}
+
+/// Formats a number of seconds as six HHMMSS digits.
+fn format_hms(secs: u64) -> String {
+    let secs = secs.min(MAX_DISPLAY_SECS);
+    format!(
+        "{:02}{:02}{:02}",
+        secs / 3600,
+        (secs % 3600) / 60,
+        secs % 60
+    )
+}
+
+/// Parses a human-friendly duration.
+///
+/// # Supported Formats
+/// - Unit suffixes, combinable: `90s`, `5m`, `1h30m`, `2h`
+/// - Clock notation: `MM:SS` or `HH:MM:SS`
+/// - A bare number of seconds: `90`
+///
+/// # Returns
+/// * `Ok(Duration)` - The parsed, non-zero duration
+/// * `Err(String)` - A message describing why the input is invalid
+pub fn parse_duration(input: &str) -> Result<Duration, String> {
+    let text = input.trim().to_ascii_lowercase();
+    let invalid = || format!("Invalid duration '{}' (try 90s, 5m, 1h30m or 1:30)", input);
+    let too_long = || format!("Duration '{}' is too long", input);
+
+    let secs = if text.contains(':') {
+        let parts: Vec<u64> = text
+            .split(':')
+            .map(|p| p.parse::<u64>().map_err(|_| invalid()))
+            .collect::<Result<_, _>>()?;
+        let (h, m, s) = match parts.as_slice() {
+            [m, s] => (0, *m, *s),
+            [h, m, s] => (*h, *m, *s),
+            _ => return Err(invalid()),
+        };
+        h.checked_mul(3600)
+            .and_then(|h| h.checked_add(m.checked_mul(60)?))
+            .and_then(|hm| hm.checked_add(s))
+            .ok_or_else(too_long)?
+    } else if let Ok(secs) = text.parse::<u64>() {
+        secs
+    } else {
+        let mut total: u64 = 0;
+        let mut number = String::new();
+        for ch in text.chars() {
+            if ch.is_ascii_digit() {
+                number.push(ch);
+                continue;
+            }
+            let value: u64 = number.parse().map_err(|_| invalid())?;
+            number.clear();
+            let unit = match ch {
+                'h' => 3600,
+                'm' => 60,
+                's' => 1,
+                _ => return Err(invalid()),
+            };
+            total = value
+                .checked_mul(unit)
+                .and_then(|secs| total.checked_add(secs))
+                .ok_or_else(too_long)?;
+        }
+        if !number.is_empty() {
+            return Err(invalid());
+        }
+        total
+    };
+
+    if secs == 0 {
+        return Err(invalid());
+    }
+
+    Ok(Duration::from_secs(secs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_duration_units() {
+        assert_eq!(parse_duration("90s"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("5m"), Ok(Duration::from_secs(300)));
+        assert_eq!(parse_duration("1h30m"), Ok(Duration::from_secs(5400)));
+        assert_eq!(parse_duration("2H"), Ok(Duration::from_secs(7200)));
+    }
+
+    #[test]
+    fn test_parse_duration_clock_notation() {
+        assert_eq!(parse_duration("1:30"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("1:00:05"), Ok(Duration::from_secs(3605)));
+        assert_eq!(parse_duration("45"), Ok(Duration::from_secs(45)));
+    }
+
+    #[test]
+    fn test_parse_duration_invalid() {
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("0").is_err());
+        assert!(parse_duration("5x").is_err());
+        assert!(parse_duration("m5").is_err());
+        assert!(parse_duration("5m3").is_err());
+        assert!(parse_duration("1:2:3:4").is_err());
+    }
+
+    #[test]
+    fn test_clock_mode_has_no_digits() {
+        let state = ModeState::default();
+        assert_eq!(state.digits(Instant::now()), None);
+    }
+
+    #[test]
+    fn test_timer_counts_down() {
+        let start = Instant::now();
+        let mut state = ModeState::default();
+        state.start_timer(Duration::from_secs(300), start);
+
+        assert_eq!(state.digits(start).as_deref(), Some("000500"));
+        assert_eq!(
+            state.digits(start + Duration::from_millis(1500)).as_deref(),
+            Some("000459")
+        );
+    }
+
+    #[test]
+    fn test_timer_finishes_once() {
+        let start = Instant::now();
+        let mut state = ModeState::default();
+        state.start_timer(Duration::from_secs(10), start);
+
+        assert!(!state.check_finished(start + Duration::from_secs(5)));
+        assert!(state.check_finished(start + Duration::from_secs(11)));
+        assert!(!state.check_finished(start + Duration::from_secs(12)));
+        assert!(!state.is_running());
+        assert_eq!(
+            state.digits(start + Duration::from_secs(12)).as_deref(),
+            Some("000000")
+        );
+    }
+
+    #[test]
+    fn test_stopwatch_pause_and_resume() {
+        let start = Instant::now();
+        let mut state = ModeState::default();
+        state.set_mode(DisplayMode::Stopwatch);
+        assert_eq!(state.digits(start).as_deref(), Some("000000"));
+
+        state.toggle_running(start);
+        state.toggle_running(start + Duration::from_secs(65));
+        assert!(!state.is_running());
+
+        // Paused: time does not advance
+        let later = start + Duration::from_secs(500);
+        assert_eq!(state.digits(later).as_deref(), Some("000105"));
+
+        state.toggle_running(later);
+        assert_eq!(
+            state.digits(later + Duration::from_secs(3600)).as_deref(),
+            Some("010105")
+        );
+    }
+
+    #[test]
+    fn test_format_hms_clamps() {
+        assert_eq!(format_hms(0), "000000");
+        assert_eq!(format_hms(3661), "010101");
+        assert_eq!(format_hms(1_000_000), "995959");
+    }
+
+    #[test]
+    fn test_wall_clock_formats() {
+        let now = DateTime::parse_from_rfc3339("2024-03-01T15:04:05Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        let utc = WallClock::new(TimeFormat::TwentyFourHour, "UTC").unwrap();
+        assert_eq!(utc.digits(now), "150405");
+
+        let twelve = WallClock::new(TimeFormat::TwelveHour, "UTC").unwrap();
+        assert_eq!(twelve.digits(now), "030405");
+
+        let tokyo = WallClock::new(TimeFormat::TwentyFourHour, "Asia/Tokyo").unwrap();
+        assert_eq!(tokyo.digits(now), "000405");
+        assert_eq!(
+            tokyo.time_of_day(now),
+            NaiveTime::from_hms_opt(0, 4, 5).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_ambient_has_no_digits() {
+        let mut state = ModeState::default();
+        state.set_mode(DisplayMode::Ambient);
+        state.toggle_running(Instant::now());
+        assert!(!state.is_running());
+        assert_eq!(state.digits(Instant::now()), None);
+    }
+
+    #[test]
+    fn test_ambient_cycle() {
+        let at = |h, m, s| NaiveTime::from_hms_opt(h, m, s).unwrap();
+        let ambient = AmbientConfig {
+            analog_secs: 50,
+            digital_secs: 10,
+        };
+        assert!(ambient_shows_digits(at(12, 30, 0), &ambient));
+        assert!(ambient_shows_digits(at(12, 30, 9), &ambient));
+        assert!(!ambient_shows_digits(at(12, 30, 10), &ambient));
+        assert!(!ambient_shows_digits(at(12, 30, 59), &ambient));
+
+        let hands_only = AmbientConfig {
+            digital_secs: 0,
+            ..ambient
+        };
+        assert!(!ambient_shows_digits(at(12, 30, 0), &hands_only));
+    }
+
+    #[test]
+    fn test_analog_angles() {
+        let at = |h, m, s| NaiveTime::from_hms_opt(h, m, s).unwrap();
+        assert_eq!(analog_angles(at(0, 0, 0)), (0, 0));
+        assert_eq!(analog_angles(at(3, 0, 0)), (90, 0));
+        assert_eq!(analog_angles(at(15, 30, 0)), (105, 180));
+        assert_eq!(analog_angles(at(9, 45, 30)), (292, 273));
+        assert_eq!(analog_angles(at(23, 59, 59)), (359, 359));
+    }
+
+    #[test]
+    fn test_parse_duration_overflow() {
+        assert!(parse_duration("9999999999999999h").is_err());
+        assert!(parse_duration("18446744073709551615s1s").is_err());
+        assert!(parse_duration("9999999999999999:00:00").is_err());
+        assert!(parse_duration("99999999999999999999").is_err());
+    }
+}
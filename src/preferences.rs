@@ -0,0 +1,710 @@
+//! Preferences dialog.
+//!
+//! An `adw::PreferencesDialog` with a Colors page for the `[theme]` and
+//! `[colors]` sections, and a page each for `[window]` and `[clock]`. Every
+//! change is written straight to the config files (see `config_edit`),
+//! keeping their comments and includes; spin rows wait for the value to
+//! settle first. The file watcher then reloads them, so the clock behind the
+//! dialog previews each change as it is made.
+
+use gtk4::prelude::*;
+use gtk4::{gdk, glib};
+use libadwaita as adw;
+use libadwaita::prelude::*;
+use serde::Serialize;
+use std::cell::{Cell, RefCell};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::Duration;
+
+use crate::AppContext;
+use crate::color::parse_color_or_black;
+use crate::config::{COLOR_KEYS, Config, ConfigSource, HandStyle, MAX_TRAIL_LENGTH};
+use crate::config_edit::write_setting;
+use crate::style::hex_color;
+use crate::theme;
+
+/// How long a spin row waits after the last change before saving it, so
+/// holding an arrow doesn't rewrite the config (and reload) on every step
+const SPIN_SAVE_DELAY_MS: u64 = 400;
+
+/// Writes settings to the config files read at startup of the dialog.
+struct Writer {
+    /// Config files in order of increasing precedence
+    files: Vec<PathBuf>,
+    /// Themes available to choose from
+    themes: Vec<String>,
+}
+
+impl Writer {
+    fn new(source: &ConfigSource) -> Self {
+        let path = source.path();
+        let themes_dir = theme::themes_dir(path.parent().unwrap_or(Path::new(".")));
+        // Stylesheets, theme files and images aren't config files to write to
+        let files = match Config::load(&path, &source.overrides, &source.system) {
+            Ok(result) => result
+                .source_files
+                .into_iter()
+                .filter(|file| {
+                    Some(file) != result.custom_css.as_ref()
+                        && Some(file) != result.theme_file.as_ref()
+                        && !result.images.contains(file)
+                })
+                .collect(),
+            Err(e) => e
+                .source_files
+                .into_iter()
+                .filter(|file| !file.starts_with(&themes_dir))
+                .collect(),
+        };
+        let themes = theme::names(&themes_dir);
+        Writer { files, themes }
+    }
+
+    fn write(&self, key: &str, value: impl Into<toml_edit::Value>) {
+        if let Err(e) = write_setting(&self.files, key, value.into()) {
+            eprintln!("Warning: Could not save {}: {}", key, e);
+        }
+    }
+
+    /// Returns a string setting as written in the file that decides it,
+    /// before color references and expressions are resolved.
+    fn raw_value(&self, key: &str) -> Option<String> {
+        self.files.iter().rev().find_map(|file| {
+            let table: toml::Table = fs::read_to_string(file).ok()?.parse().ok()?;
+            let (sections, name) = key.rsplit_once('.').unwrap_or(("", key));
+            let section = sections
+                .split('.')
+                .filter(|part| !part.is_empty())
+                .try_fold(&table, |table, part| table.get(part)?.as_table())?;
+            section.get(name)?.as_str().map(str::to_string)
+        })
+    }
+}
+
+/// Opens the preferences dialog over the main window.
+///
+/// # Arguments
+/// * `ctx` - Shared handles to the window, config and config source
+pub fn present(ctx: &AppContext) {
+    let config = ctx.config.borrow().clone();
+    let writer = Rc::new(Writer::new(&ctx.source.borrow()));
+
+    let dialog = adw::PreferencesDialog::new();
+    dialog.set_search_enabled(true);
+    dialog.add(&colors_page(&config, &writer));
+    dialog.add(&window_page(&config, &writer));
+    dialog.add(&clock_page(&config, &writer));
+    dialog.present(Some(&ctx.window));
+}
+
+fn colors_page(config: &Config, writer: &Rc<Writer>) -> adw::PreferencesPage {
+    let theme = adw::PreferencesGroup::builder()
+        .description(
+            "Colors set below take precedence over the theme's. Following the system style \
+             picks the light or dark theme and takes the hands' color from the accent color.",
+        )
+        .build();
+    theme.add(&theme_row(
+        "Theme",
+        &config.theme.name,
+        "theme.name",
+        writer,
+    ));
+    theme.add(&switch_row(
+        "Follow system style",
+        config.theme.follow_system,
+        "theme.follow_system",
+        writer,
+    ));
+    theme.add(&theme_row(
+        "Light theme",
+        &config.theme.light,
+        "theme.light",
+        writer,
+    ));
+    theme.add(&theme_row(
+        "Dark theme",
+        &config.theme.dark,
+        "theme.dark",
+        writer,
+    ));
+
+    let group = adw::PreferencesGroup::builder()
+        .description("Colors may be translucent")
+        .build();
+
+    for key in COLOR_KEYS {
+        if let Some(color) = config.colors.get(key) {
+            group.add(&color_row(
+                &label(key, '_'),
+                color,
+                &format!("colors.{}", key),
+                writer,
+            ));
+        }
+    }
+
+    page("Colors", "applications-graphics-symbolic", &[theme, group])
+}
+
+fn window_page(config: &Config, writer: &Rc<Writer>) -> adw::PreferencesPage {
+    let window = &config.window;
+
+    let appearance = adw::PreferencesGroup::builder().title("Appearance").build();
+    appearance.add(&spin_row(
+        "Background opacity",
+        (0.0, 1.0, 0.05),
+        2,
+        window.opacity,
+        "window.opacity",
+        writer,
+    ));
+    appearance.add(&switch_row(
+        "Transparent",
+        window.transparent,
+        "window.transparent",
+        writer,
+    ));
+    appearance.add(&switch_row(
+        "Click-through",
+        window.click_through,
+        "window.click_through",
+        writer,
+    ));
+    appearance.add(&entry_row(
+        "Custom CSS file",
+        &window.custom_css,
+        "window.custom_css",
+        writer,
+    ));
+
+    let placement = adw::PreferencesGroup::builder()
+        .title("Placement")
+        .description("Switching to or from widget mode takes effect after a restart")
+        .build();
+    placement.add(&combo_row(
+        "Mode",
+        &["normal", "widget", "fullscreen"],
+        &value_name(&window.mode),
+        "window.mode",
+        writer,
+    ));
+    placement.add(&combo_row(
+        "Widget position",
+        &[
+            "top-left",
+            "top",
+            "top-right",
+            "left",
+            "center",
+            "right",
+            "bottom-left",
+            "bottom",
+            "bottom-right",
+        ],
+        &value_name(&window.anchor),
+        "window.anchor",
+        writer,
+    ));
+    placement.add(&spin_row(
+        "Widget margin",
+        (0.0, 500.0, 1.0),
+        0,
+        f64::from(window.margin),
+        "window.margin",
+        writer,
+    ));
+
+    let display = &config.display;
+    let container = adw::PreferencesGroup::builder()
+        .title("Clock Container")
+        .build();
+    container.add(&spin_row(
+        "Padding",
+        (0.0, 200.0, 1.0),
+        0,
+        f64::from(display.padding),
+        "display.padding",
+        writer,
+    ));
+    container.add(&spin_row(
+        "Corner radius",
+        (0.0, 200.0, 1.0),
+        0,
+        f64::from(display.border_radius),
+        "display.border_radius",
+        writer,
+    ));
+    container.add(&spin_row(
+        "Border width",
+        (0.0, 20.0, 1.0),
+        0,
+        f64::from(display.border_width),
+        "display.border_width",
+        writer,
+    ));
+
+    page(
+        "Window",
+        "preferences-desktop-display-symbolic",
+        &[appearance, container, placement],
+    )
+}
+
+fn clock_page(config: &Config, writer: &Rc<Writer>) -> adw::PreferencesPage {
+    let clock = &config.clock;
+
+    let layout = adw::PreferencesGroup::builder().title("Layout").build();
+    layout.add(&spin_row(
+        "Clock size",
+        (8.0, 400.0, 1.0),
+        0,
+        f64::from(clock.size),
+        "clock.size",
+        writer,
+    ));
+    layout.add(&spin_row(
+        "Hand width",
+        (0.5, 20.0, 0.5),
+        1,
+        clock.stroke_width,
+        "clock.stroke_width",
+        writer,
+    ));
+    layout.add(&spin_row(
+        "Gap between clocks",
+        (0.0, 50.0, 1.0),
+        0,
+        f64::from(clock.clock_gap),
+        "clock.clock_gap",
+        writer,
+    ));
+    layout.add(&spin_row(
+        "Gap between digits",
+        (0.0, 200.0, 1.0),
+        0,
+        f64::from(clock.digit_gap),
+        "clock.digit_gap",
+        writer,
+    ));
+    layout.add(&spin_row(
+        "Animation duration (ms)",
+        (0.0, 5000.0, 50.0),
+        0,
+        clock.animation_duration_ms as f64,
+        "clock.animation_duration_ms",
+        writer,
+    ));
+
+    let time = adw::PreferencesGroup::builder().title("Time").build();
+    time.add(&combo_row(
+        "Startup mode",
+        &["clock", "timer", "stopwatch", "ambient"],
+        &value_name(&clock.mode),
+        "clock.mode",
+        writer,
+    ));
+    time.add(&combo_row(
+        "Hour format",
+        &["24h", "12h"],
+        &value_name(&clock.format),
+        "clock.format",
+        writer,
+    ));
+    time.add(&entry_row(
+        "Timezone (empty for local time)",
+        &clock.timezone,
+        "clock.timezone",
+        writer,
+    ));
+    time.add(&spin_row(
+        "Ambient mode: seconds on the hands",
+        (0.0, 3600.0, 5.0),
+        0,
+        config.ambient.analog_secs as f64,
+        "ambient.analog_secs",
+        writer,
+    ));
+    time.add(&spin_row(
+        "Ambient mode: seconds as digits",
+        (0.0, 3600.0, 5.0),
+        0,
+        config.ambient.digital_secs as f64,
+        "ambient.digital_secs",
+        writer,
+    ));
+
+    let face = &config.face;
+    let faces = adw::PreferencesGroup::builder()
+        .title("Face")
+        .description(
+            "[face.active] and [face.inactive] in the config file can style digit and \
+             resting clocks apart",
+        )
+        .build();
+    faces.add(&combo_row(
+        "Shape",
+        &["circle", "square", "rounded-square", "none"],
+        &value_name(&face.style),
+        "face.style",
+        writer,
+    ));
+    faces.add(&combo_row(
+        "Tick marks",
+        &["none", "quarters", "hours"],
+        &value_name(&face.ticks),
+        "face.ticks",
+        writer,
+    ));
+    faces.add(&combo_row(
+        "Bevel",
+        &["none", "inset", "raised"],
+        &value_name(&face.bevel),
+        "face.bevel",
+        writer,
+    ));
+    faces.add(&spin_row(
+        "Border width",
+        (0.0, 10.0, 0.5),
+        1,
+        face.border_width,
+        "face.border_width",
+        writer,
+    ));
+
+    let effects = &config.effects;
+    let glow = adw::PreferencesGroup::builder()
+        .title("Effects")
+        .description("Each effect draws the hands several more times")
+        .build();
+    glow.add(&spin_row(
+        "Glow radius (0 for none)",
+        (0.0, 20.0, 0.5),
+        1,
+        effects.glow_radius,
+        "effects.glow_radius",
+        writer,
+    ));
+    glow.add(&spin_row(
+        "Glow intensity",
+        (0.0, 1.0, 0.05),
+        2,
+        effects.glow_intensity,
+        "effects.glow_intensity",
+        writer,
+    ));
+    glow.add(&switch_row(
+        "Drop shadow",
+        effects.shadow,
+        "effects.shadow",
+        writer,
+    ));
+    glow.add(&spin_row(
+        "Shadow softness",
+        (0.0, 10.0, 0.5),
+        1,
+        effects.shadow_blur,
+        "effects.shadow_blur",
+        writer,
+    ));
+    glow.add(&spin_row(
+        "Motion trail length (0 for none)",
+        (0.0, f64::from(MAX_TRAIL_LENGTH), 1.0),
+        0,
+        f64::from(effects.trail_length),
+        "effects.trail_length",
+        writer,
+    ));
+    glow.add(&spin_row(
+        "Motion trail intensity",
+        (0.0, 1.0, 0.05),
+        2,
+        effects.trail_intensity,
+        "effects.trail_intensity",
+        writer,
+    ));
+
+    page(
+        "Clock",
+        "preferences-system-time-symbolic",
+        &[
+            layout,
+            faces,
+            hand_group("Hour Hand", &config.hands.hour, "hands.hour", writer),
+            hand_group("Minute Hand", &config.hands.minute, "hands.minute", writer),
+            glow,
+            time,
+        ],
+    )
+}
+
+/// Builds the group of settings shaping one clock hand.
+///
+/// # Arguments
+/// * `title` - Title of the group
+/// * `hand` - The hand's current shape
+/// * `table` - Config table of the hand, e.g. `hands.hour`
+/// * `writer` - Where changes are saved
+fn hand_group(
+    title: &str,
+    hand: &HandStyle,
+    table: &str,
+    writer: &Rc<Writer>,
+) -> adw::PreferencesGroup {
+    let group = adw::PreferencesGroup::builder().title(title).build();
+    group.add(&spin_row(
+        "Length",
+        (0.05, 1.5, 0.05),
+        2,
+        hand.length,
+        &format!("{}.length", table),
+        writer,
+    ));
+    group.add(&spin_row(
+        "Width (0 for the hand width above)",
+        (0.0, 20.0, 0.5),
+        1,
+        hand.width,
+        &format!("{}.width", table),
+        writer,
+    ));
+    group.add(&combo_row(
+        "Ends",
+        &["round", "butt", "square"],
+        &value_name(&hand.cap),
+        &format!("{}.cap", table),
+        writer,
+    ));
+    group.add(&spin_row(
+        "Taper",
+        (0.0, 1.0, 0.05),
+        2,
+        hand.taper,
+        &format!("{}.taper", table),
+        writer,
+    ));
+    group.add(&spin_row(
+        "Counterweight",
+        (0.0, 1.0, 0.05),
+        2,
+        hand.tail,
+        &format!("{}.tail", table),
+        writer,
+    ));
+    group
+}
+
+fn page(title: &str, icon: &str, groups: &[adw::PreferencesGroup]) -> adw::PreferencesPage {
+    let page = adw::PreferencesPage::builder()
+        .title(title)
+        .icon_name(icon)
+        .build();
+    for group in groups {
+        page.add(group);
+    }
+    page
+}
+
+/// A combo row choosing between the built-in and user themes.
+fn theme_row(title: &str, current: &str, key: &str, writer: &Rc<Writer>) -> adw::ComboRow {
+    let names: Vec<&str> = writer.themes.iter().map(String::as_str).collect();
+    let row = adw::ComboRow::builder()
+        .title(title)
+        .model(&gtk4::StringList::new(&names))
+        .build();
+    if let Some(index) = names.iter().position(|name| *name == current) {
+        row.set_selected(index as u32);
+    }
+
+    let key = key.to_string();
+    row.connect_selected_notify(glib::clone!(
+        #[strong]
+        writer,
+        move |row| {
+            if let Some(name) = writer.themes.get(row.selected() as usize) {
+                writer.write(&key, name.as_str());
+            }
+        }
+    ));
+    row
+}
+
+/// A row with a color picker, saving colors as `#RRGGBB` or `#RRGGBBAA`.
+///
+/// A color set as a reference or expression, such as `$accent / 15%`, is
+/// shown as the row's subtitle. It is only replaced when a different color
+/// is picked.
+fn color_row(title: &str, color: &str, key: &str, writer: &Rc<Writer>) -> adw::ActionRow {
+    let (r, g, b, a) = parse_color_or_black(color);
+    let button = gtk4::ColorDialogButton::new(Some(gtk4::ColorDialog::new()));
+    button.set_rgba(&gdk::RGBA::new(r as f32, g as f32, b as f32, a as f32));
+    button.set_valign(gtk4::Align::Center);
+
+    let row = adw::ActionRow::builder().title(title).build();
+    if let Some(expression) = writer
+        .raw_value(key)
+        .filter(|raw| !raw.trim_start().starts_with('#'))
+    {
+        row.set_subtitle(&expression);
+    }
+
+    let key = key.to_string();
+    let shown = RefCell::new(hex_color(&button.rgba()));
+    button.connect_rgba_notify(glib::clone!(
+        #[strong]
+        writer,
+        #[weak]
+        row,
+        move |button| {
+            let picked = hex_color(&button.rgba());
+            if *shown.borrow() != picked {
+                writer.write(&key, picked.as_str());
+                row.set_subtitle("");
+                shown.replace(picked);
+            }
+        }
+    ));
+
+    row.add_suffix(&button);
+    row.set_activatable_widget(Some(&button));
+    row
+}
+
+/// A spin row saving integers when `digits` is 0 and floats otherwise.
+///
+/// Changes are saved once the value has settled for `SPIN_SAVE_DELAY_MS`.
+fn spin_row(
+    title: &str,
+    (min, max, step): (f64, f64, f64),
+    digits: u32,
+    value: f64,
+    key: &str,
+    writer: &Rc<Writer>,
+) -> adw::SpinRow {
+    let row = adw::SpinRow::with_range(min, max, step);
+    row.set_title(title);
+    row.set_digits(digits);
+    row.set_value(value);
+
+    let key = Rc::new(key.to_string());
+    let pending: Rc<Cell<Option<glib::SourceId>>> = Rc::default();
+    row.connect_value_notify(glib::clone!(
+        #[strong]
+        writer,
+        move |row| {
+            if let Some(source) = pending.take() {
+                source.remove();
+            }
+            let value = row.value();
+            let save = glib::clone!(
+                #[strong]
+                writer,
+                #[strong]
+                key,
+                #[strong]
+                pending,
+                move || {
+                    pending.set(None);
+                    if digits == 0 {
+                        writer.write(&key, value.round() as i64);
+                    } else {
+                        // Round away float noise such as 0.30000000000000004
+                        let scale = 10f64.powi(digits as i32);
+                        writer.write(&key, (value * scale).round() / scale);
+                    }
+                }
+            );
+            pending.set(Some(glib::timeout_add_local_once(
+                Duration::from_millis(SPIN_SAVE_DELAY_MS),
+                save,
+            )));
+        }
+    ));
+    row
+}
+
+fn switch_row(title: &str, active: bool, key: &str, writer: &Rc<Writer>) -> adw::SwitchRow {
+    let row = adw::SwitchRow::builder()
+        .title(title)
+        .active(active)
+        .build();
+
+    let key = key.to_string();
+    row.connect_active_notify(glib::clone!(
+        #[strong]
+        writer,
+        move |row| writer.write(&key, row.is_active())
+    ));
+    row
+}
+
+/// A combo row choosing between the given config values.
+fn combo_row(
+    title: &str,
+    options: &'static [&'static str],
+    current: &str,
+    key: &str,
+    writer: &Rc<Writer>,
+) -> adw::ComboRow {
+    let row = adw::ComboRow::builder()
+        .title(title)
+        .model(&gtk4::StringList::new(options))
+        .build();
+    if let Some(index) = options.iter().position(|option| *option == current) {
+        row.set_selected(index as u32);
+    }
+
+    let key = key.to_string();
+    row.connect_selected_notify(glib::clone!(
+        #[strong]
+        writer,
+        move |row| {
+            if let Some(option) = options.get(row.selected() as usize) {
+                writer.write(&key, *option);
+            }
+        }
+    ));
+    row
+}
+
+/// An entry row saving its text when the apply button is pressed.
+fn entry_row(title: &str, text: &str, key: &str, writer: &Rc<Writer>) -> adw::EntryRow {
+    let row = adw::EntryRow::builder()
+        .title(title)
+        .text(text)
+        .show_apply_button(true)
+        .build();
+
+    let key = key.to_string();
+    row.connect_apply(glib::clone!(
+        #[strong]
+        writer,
+        move |row| writer.write(&key, row.text().as_str())
+    ));
+    row
+}
+
+/// Turns a name into a label, e.g. the config key `clock_hand_color` into
+/// "Clock hand color" or the theme `high-contrast` into "High contrast".
+///
+/// # Arguments
+/// * `name` - The name to turn into a label
+/// * `separator` - The character between the name's words
+pub fn label(name: &str, separator: char) -> String {
+    let text = name.replace(separator, " ");
+    let mut chars = text.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => text,
+    }
+}
+
+/// Returns the name an enum setting is written as in the config.
+fn value_name(value: &impl Serialize) -> String {
+    match toml::Value::try_from(value) {
+        Ok(toml::Value::String(name)) => name,
+        _ => String::new(),
+    }
+}
@@ -0,0 +1,178 @@
+//! Time-of-day color schedules.
+//!
+//! `[[colors.schedule]]` entries each set some colors from a time of day:
+//!
+//! ```toml
+//! [[colors.schedule]]
+//! time = "07:00"
+//! clock_hand_color = "#7aa2f7"
+//!
+//! [[colors.schedule]]
+//! time = "21:00"
+//! clock_hand_color = "#ff9e64"
+//! ```
+//!
+//! Each color is scheduled on its own: it follows the entries that set it,
+//! around the clock, and keeps its `[colors]` value if no entry does. With
+//! `schedule_mode = "smooth"` a color blends from one entry's value to the
+//! next over the time between them; with `"step"` it switches at each
+//! entry's time. The display re-evaluates the schedule every second.
+
+use chrono::{NaiveTime, Timelike};
+
+use crate::color::{format_hex_color, parse_color};
+use crate::config::{COLOR_KEYS, ColorConfig, ScheduleMode};
+
+/// Minutes in a day, the length of the schedule's cycle.
+const MINUTES_PER_DAY: f64 = 24.0 * 60.0;
+
+/// Parses a time of day written as `HH:MM` (24-hour).
+///
+/// # Returns
+/// * `Ok(minutes)` - Minutes since midnight
+/// * `Err(String)` - Why the time is invalid
+pub fn parse_time_of_day(text: &str) -> Result<u32, String> {
+    let invalid = || format!("invalid time '{}' (expected HH:MM, e.g. 07:30)", text);
+    let (hours, minutes) = text.trim().split_once(':').ok_or_else(invalid)?;
+    let hours: u32 = hours.parse().map_err(|_| invalid())?;
+    let minutes: u32 = minutes.parse().map_err(|_| invalid())?;
+    if hours > 23 || minutes > 59 {
+        return Err(invalid());
+    }
+    Ok(hours * 60 + minutes)
+}
+
+/// Returns the colors the schedule gives for a time of day.
+///
+/// Invalid entries are skipped; `Config::validate` reports them.
+///
+/// # Arguments
+/// * `colors` - The `[colors]` section, with its schedule
+/// * `time` - Time of day to evaluate the schedule at
+///
+/// # Returns
+/// A copy of `colors` with every scheduled color set to its current value
+pub fn colors_at(colors: &ColorConfig, time: NaiveTime) -> ColorConfig {
+    let mut result = colors.clone();
+    if colors.schedule.is_empty() {
+        return result;
+    }
+    let now = f64::from(time.num_seconds_from_midnight()) / 60.0;
+
+    for key in COLOR_KEYS {
+        // (minute of day, color) for every entry setting this key, by time
+        let mut points: Vec<(f64, (f64, f64, f64, f64))> = colors
+            .schedule
+            .iter()
+            .filter_map(|point| {
+                let minute = parse_time_of_day(&point.time).ok()?;
+                let color = parse_color(point.colors.get(key)?).ok()?;
+                Some((f64::from(minute), color))
+            })
+            .collect();
+        if points.is_empty() {
+            continue;
+        }
+        points.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        // The latest entry at or before now, wrapping to the last one of the
+        // previous day, and the entry after it
+        let index = points
+            .iter()
+            .rposition(|(minute, _)| *minute <= now)
+            .unwrap_or(points.len() - 1);
+        let (from_minute, from) = points[index];
+        let (to_minute, to) = points[(index + 1) % points.len()];
+
+        let color = match colors.schedule_mode {
+            ScheduleMode::Step => from,
+            ScheduleMode::Smooth => {
+                let span = (to_minute - from_minute).rem_euclid(MINUTES_PER_DAY);
+                let elapsed = (now - from_minute).rem_euclid(MINUTES_PER_DAY);
+                if span == 0.0 {
+                    from
+                } else {
+                    lerp(from, to, elapsed / span)
+                }
+            }
+        };
+        if let Some(value) = result.get_mut(key) {
+            *value = format_hex_color(color);
+        }
+    }
+
+    result
+}
+
+/// Blends two RGBA colors by `t` (0.0 = `from`, 1.0 = `to`).
+fn lerp(from: (f64, f64, f64, f64), to: (f64, f64, f64, f64), t: f64) -> (f64, f64, f64, f64) {
+    (
+        from.0 + (to.0 - from.0) * t,
+        from.1 + (to.1 - from.1) * t,
+        from.2 + (to.2 - from.2) * t,
+        from.3 + (to.3 - from.3) * t,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ColorSchedulePoint;
+
+    fn point(time: &str, hand: &str) -> ColorSchedulePoint {
+        ColorSchedulePoint {
+            time: time.to_string(),
+            colors: [("clock_hand_color".to_string(), hand.to_string())].into(),
+        }
+    }
+
+    fn hand_at(colors: &ColorConfig, hour: u32, minute: u32) -> String {
+        let time = NaiveTime::from_hms_opt(hour, minute, 0).unwrap();
+        colors_at(colors, time).clock_hand_color
+    }
+
+    #[test]
+    fn test_parse_time_of_day() {
+        assert_eq!(parse_time_of_day("00:00"), Ok(0));
+        assert_eq!(parse_time_of_day("7:30"), Ok(450));
+        assert_eq!(parse_time_of_day("23:59"), Ok(1439));
+        assert!(parse_time_of_day("24:00").is_err());
+        assert!(parse_time_of_day("12:60").is_err());
+        assert!(parse_time_of_day("noon").is_err());
+    }
+
+    #[test]
+    fn test_step_schedule_wraps_around_midnight() {
+        let colors = ColorConfig {
+            schedule: vec![point("20:00", "#ff0000"), point("08:00", "#0000ff")],
+            schedule_mode: ScheduleMode::Step,
+            ..ColorConfig::default()
+        };
+        assert_eq!(hand_at(&colors, 7, 59), "#ff0000");
+        assert_eq!(hand_at(&colors, 8, 0), "#0000ff");
+        assert_eq!(hand_at(&colors, 19, 59), "#0000ff");
+        assert_eq!(hand_at(&colors, 23, 0), "#ff0000");
+        // Unscheduled colors keep their value
+        let time = NaiveTime::from_hms_opt(12, 0, 0).unwrap();
+        assert_eq!(colors_at(&colors, time).clock_bg, colors.clock_bg);
+    }
+
+    #[test]
+    fn test_smooth_schedule_interpolates() {
+        let colors = ColorConfig {
+            schedule: vec![point("06:00", "#000000"), point("18:00", "#ffffff")],
+            ..ColorConfig::default()
+        };
+        assert_eq!(hand_at(&colors, 6, 0), "#000000");
+        assert_eq!(hand_at(&colors, 12, 0), "#808080");
+        assert_eq!(hand_at(&colors, 18, 0), "#ffffff");
+        // Back to black across midnight
+        assert_eq!(hand_at(&colors, 0, 0), "#808080");
+
+        let single = ColorConfig {
+            schedule: vec![point("06:00", "#123456")],
+            ..ColorConfig::default()
+        };
+        assert_eq!(hand_at(&single, 3, 0), "#123456");
+    }
+}
@@ -0,0 +1,295 @@
+//! Application stylesheet.
+//!
+//! All styling goes through a single `CssProvider`, created once and reloaded
+//! in place whenever the configuration changes. It holds:
+//! - Rules generated from the config (window background with opacity, and the
+//!   `.clock-display` container's background, border and padding), with the
+//!   gradients and images of `window.background` and `display.background`
+//! - A `window.transparent` rule removing the background and shadow, used
+//!   when `window.transparent` is set (see `window`)
+//! - The contents of the optional `window.custom_css` file, appended after the
+//!   generated rules so user rules win
+//!
+//! Scheduled colors (see `schedule`) are passed in with `set_colors`, which
+//! only reloads the provider when the generated rules actually change.
+//!
+//! CSS errors in the custom file are reported with its own line numbers.
+//!
+//! It also reads the desktop's dark/light preference and accent color from
+//! `adw::StyleManager`, for `theme.follow_system`.
+
+use gtk4::prelude::*;
+use gtk4::{CssProvider, gdk, gio, glib};
+use libadwaita as adw;
+use std::cell::{Cell, RefCell};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+use crate::color::{format_hex_color, parse_color_or_black};
+use crate::config::{BackgroundConfig, ColorConfig, Config, Gradient, ImageMode};
+use crate::theme::SystemStyle;
+
+/// The app-wide CSS provider and what was last loaded into it.
+pub struct Stylesheet {
+    provider: CssProvider,
+    /// The custom CSS file currently loaded, if any
+    custom_css: Rc<RefCell<Option<PathBuf>>>,
+    /// Line of the combined stylesheet where the custom CSS begins
+    custom_css_line: Rc<Cell<usize>>,
+    /// Config the stylesheet was last generated from
+    config: RefCell<Config>,
+}
+
+impl Stylesheet {
+    /// Creates the provider and installs it on the default display.
+    ///
+    /// Parse errors are reported on stderr. Errors in the custom CSS file are
+    /// reported against that file's own lines.
+    pub fn new() -> Self {
+        let provider = CssProvider::new();
+        let custom_css: Rc<RefCell<Option<PathBuf>>> = Rc::new(RefCell::new(None));
+        let custom_css_line = Rc::new(Cell::new(0));
+
+        provider.connect_parsing_error(glib::clone!(
+            #[strong]
+            custom_css,
+            #[strong]
+            custom_css_line,
+            move |_, section, error| {
+                let location = section.start_location();
+                let first_line = custom_css_line.get();
+                match custom_css.borrow().as_ref() {
+                    Some(path) if location.lines() >= first_line => eprintln!(
+                        "Warning: {}:{}:{}: {}",
+                        path.display(),
+                        location.lines() - first_line + 1,
+                        location.line_chars() + 1,
+                        error.message()
+                    ),
+                    _ => eprintln!("Warning: generated CSS: {}", error.message()),
+                }
+            }
+        ));
+
+        gtk4::style_context_add_provider_for_display(
+            &gdk::Display::default().expect("Could not connect to a display."),
+            &provider,
+            gtk4::STYLE_PROVIDER_PRIORITY_APPLICATION,
+        );
+
+        Stylesheet {
+            provider,
+            custom_css,
+            custom_css_line,
+            config: RefCell::new(Config::default()),
+        }
+    }
+
+    /// Regenerates the stylesheet from a config, replacing the previous one.
+    ///
+    /// # Arguments
+    /// * `config` - The configuration containing color and opacity settings
+    /// * `custom_css` - Resolved path of the user's CSS file, if configured
+    pub fn apply(&self, config: &Config, custom_css: Option<&Path>) {
+        let mut css = generated_css(config);
+
+        let mut loaded = None;
+        if let Some(path) = custom_css {
+            match fs::read_to_string(path) {
+                Ok(text) => {
+                    css.push('\n');
+                    self.custom_css_line.set(css.matches('\n').count());
+                    css.push_str(&text);
+                    loaded = Some(path.to_path_buf());
+                }
+                Err(e) => eprintln!("Warning: Could not read custom CSS {:?}: {}", path, e),
+            }
+        }
+        *self.custom_css.borrow_mut() = loaded;
+        *self.config.borrow_mut() = config.clone();
+
+        self.provider.load_from_string(&css);
+    }
+
+    /// Regenerates the stylesheet with different colors, keeping the rest of
+    /// the last applied config and custom CSS.
+    ///
+    /// Does nothing if the generated rules stay the same.
+    ///
+    /// # Arguments
+    /// * `colors` - The colors to style the window with
+    pub fn set_colors(&self, colors: &ColorConfig) {
+        let config = Config {
+            colors: colors.clone(),
+            ..self.config.borrow().clone()
+        };
+        if generated_css(&config) == generated_css(&self.config.borrow()) {
+            return;
+        }
+        let custom_css = self.custom_css.borrow().clone();
+        self.apply(&config, custom_css.as_deref());
+    }
+}
+
+/// Generates CSS rules from the config.
+///
+/// This handles:
+/// - Window background color with opacity, and its gradient and image
+/// - Display container styling (background, border, padding, border-radius)
+///
+/// The window opacity setting from config is applied to the alpha channel of
+/// the background colors, allowing transparent backgrounds while keeping UI
+/// elements opaque. Background images are drawn as they are.
+fn generated_css(config: &Config) -> String {
+    // Multiply alpha by the opacity setting to control background transparency
+    let opacity = config.window.opacity;
+    let bg_rgba = css_rgba(
+        parse_color_or_black(&config.colors.window_background),
+        opacity,
+    );
+    let window_background = background_css(&config.window.background, &config.colors, opacity);
+
+    let display = &config.display;
+    let display_rgba = css_rgba(parse_color_or_black(&config.colors.display_bg), 1.0);
+    let display_background = background_css(&display.background, &config.colors, 1.0);
+    let border_rgba = css_rgba(parse_color_or_black(&config.colors.display_border), 1.0);
+    let (padding, border_radius, border_width) =
+        (display.padding, display.border_radius, display.border_width);
+
+    format!(
+        r#"
+        window {{
+            background-color: {bg_rgba};
+            {window_background}
+        }}
+
+        window.transparent,
+        window.transparent.csd {{
+            background: none;
+            box-shadow: none;
+        }}
+
+        .clock-display {{
+            padding: {padding}px;
+            background-color: {display_rgba};
+            {display_background}
+            border-radius: {border_radius}px;
+            border: {border_width}px solid {border_rgba};
+        }}
+        "#
+    )
+}
+
+/// Formats a color as a CSS `rgba()` value, with its alpha scaled by
+/// `opacity`.
+fn css_rgba((r, g, b, a): (f64, f64, f64, f64), opacity: f64) -> String {
+    format!(
+        "rgba({}, {}, {}, {})",
+        (r * 255.0) as u8,
+        (g * 255.0) as u8,
+        (b * 255.0) as u8,
+        a * opacity
+    )
+}
+
+/// Generates the background image properties for a gradient and an image,
+/// the image on top.
+///
+/// # Arguments
+/// * `background` - The gradient and image settings
+/// * `colors` - Colors the gradient's colors may refer to
+/// * `opacity` - Factor for the alpha of the gradient's colors
+fn background_css(background: &BackgroundConfig, colors: &ColorConfig, opacity: f64) -> String {
+    // One (image, size, repeat) per layer, topmost first
+    let mut layers = Vec::new();
+
+    if !background.image.is_empty() {
+        let uri = gio::File::for_path(&background.image).uri();
+        let (size, repeat) = match background.image_mode {
+            ImageMode::Cover => ("cover", "no-repeat"),
+            ImageMode::Contain => ("contain", "no-repeat"),
+            ImageMode::Stretch => ("100% 100%", "no-repeat"),
+            ImageMode::Tile => ("auto", "repeat"),
+        };
+        layers.push((format!("url(\"{}\")", uri), size, repeat));
+    }
+
+    if background.gradient_colors.len() >= 2 {
+        let stops: Vec<String> = background
+            .gradient_colors
+            .iter()
+            .map(|color| {
+                let color = colors.eval(color).unwrap_or_else(|e| {
+                    eprintln!("Warning: {}, using black", e);
+                    (0.0, 0.0, 0.0, 1.0)
+                });
+                css_rgba(color, opacity)
+            })
+            .collect();
+        let stops = stops.join(", ");
+        let gradient = match background.gradient {
+            Gradient::None => None,
+            Gradient::Linear => Some(format!(
+                "linear-gradient({}deg, {})",
+                background.gradient_angle, stops
+            )),
+            Gradient::Radial => Some(format!("radial-gradient(circle, {})", stops)),
+        };
+        if let Some(gradient) = gradient {
+            layers.push((gradient, "auto", "no-repeat"));
+        }
+    }
+
+    if layers.is_empty() {
+        return "background-image: none;".to_string();
+    }
+    let list = |part: fn(&(String, &str, &str)) -> String| {
+        layers.iter().map(part).collect::<Vec<_>>().join(", ")
+    };
+    [
+        format!("background-image: {};", list(|layer| layer.0.clone())),
+        format!("background-size: {};", list(|layer| layer.1.to_string())),
+        format!("background-repeat: {};", list(|layer| layer.2.to_string())),
+        "background-position: center;".to_string(),
+    ]
+    .join("\n")
+}
+
+/// Reads the desktop's current appearance.
+///
+/// # Returns
+/// The dark/light preference, and the accent color if the desktop provides
+/// one (otherwise libadwaita would report its default blue)
+pub fn system_style() -> SystemStyle {
+    let manager = adw::StyleManager::default();
+    SystemStyle {
+        dark: manager.is_dark(),
+        accent: manager
+            .is_system_supports_accent_colors()
+            .then(|| hex_color(&manager.accent_color_rgba())),
+    }
+}
+
+/// Calls `f` whenever the desktop's dark/light preference or accent color
+/// changes.
+pub fn connect_system_style_changed(f: impl Fn() + 'static) {
+    let manager = adw::StyleManager::default();
+    let f = Rc::new(f);
+    manager.connect_dark_notify(glib::clone!(
+        #[strong]
+        f,
+        move |_| f()
+    ));
+    manager.connect_accent_color_rgba_notify(move |_| f());
+}
+
+/// Formats a color as `#RRGGBB`, or `#RRGGBBAA` when it is translucent.
+pub fn hex_color(rgba: &gdk::RGBA) -> String {
+    format_hex_color((
+        f64::from(rgba.red()),
+        f64::from(rgba.green()),
+        f64::from(rgba.blue()),
+        f64::from(rgba.alpha()),
+    ))
+}
@@ -0,0 +1,224 @@
+//! Named theme presets.
+//!
+//! A theme is a partial config, usually just a `[colors]` table, selected
+//! with `[theme] name = "..."`. The built-in themes are compiled into the
+//! binary from the `themes/` directory of the source tree. User themes are
+//! `<name>.toml` files in the `themes` directory next to the main config
+//! (`~/.config/chronomatrix/themes` by default), and a user theme with the
+//! name of a built-in one replaces it.
+//!
+//! `Config::load` layers the selected theme under the config files, so any
+//! color set in the config or its includes still wins over the theme.
+//!
+//! With `theme.follow_system`, the desktop's dark/light preference picks
+//! between the `theme.light` and `theme.dark` themes instead, and its accent
+//! color replaces the theme's hand and separator colors. The desktop side is
+//! passed in as a `SystemStyle` (see `style::system_style`).
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml::value::Table;
+
+/// Name of the theme used when none is configured.
+pub const DEFAULT_THEME: &str = "default";
+
+/// Built-in themes as `(name, TOML)` pairs, in menu order.
+pub const BUILTIN: [(&str, &str); 7] = [
+    ("default", include_str!("../themes/default.toml")),
+    ("dark", include_str!("../themes/dark.toml")),
+    ("light", include_str!("../themes/light.toml")),
+    ("solarized", include_str!("../themes/solarized.toml")),
+    ("nord", include_str!("../themes/nord.toml")),
+    ("gruvbox", include_str!("../themes/gruvbox.toml")),
+    (
+        "high-contrast",
+        include_str!("../themes/high-contrast.toml"),
+    ),
+];
+
+/// The desktop's appearance, used by `theme.follow_system`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SystemStyle {
+    /// Whether the desktop prefers dark colors
+    pub dark: bool,
+    /// Accent color as `#RRGGBB`, if the desktop has one
+    pub accent: Option<String>,
+}
+
+impl SystemStyle {
+    /// Returns the colors derived from the accent color, as a config table
+    /// to layer over a theme, or `None` without an accent color.
+    pub fn accent_colors(&self) -> Option<Table> {
+        let accent = toml::Value::String(self.accent.clone()?);
+        let mut colors = Table::new();
+        colors.insert("clock_hand_color".to_string(), accent.clone());
+        colors.insert("separator_color".to_string(), accent);
+
+        let mut table = Table::new();
+        table.insert("colors".to_string(), toml::Value::Table(colors));
+        Some(table)
+    }
+}
+
+/// Where a theme is defined.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ThemeSource {
+    /// Compiled in, with its TOML contents
+    Builtin(&'static str),
+    /// A user theme file
+    File(PathBuf),
+}
+
+/// Returns the user themes directory for a config file directory.
+///
+/// # Arguments
+/// * `config_dir` - Directory of the main config file
+pub fn themes_dir(config_dir: &Path) -> PathBuf {
+    config_dir.join("themes")
+}
+
+/// Finds a theme by name, preferring a user theme over a built-in one.
+///
+/// # Arguments
+/// * `name` - Theme name, e.g. `nord`
+/// * `dir` - User themes directory (see `themes_dir`)
+///
+/// # Returns
+/// Where the theme is defined, or `None` if there is no such theme
+pub fn find(name: &str, dir: &Path) -> Option<ThemeSource> {
+    // Names are file stems; anything path-like can't name a theme
+    if name.is_empty() || name.contains(['/', '\\']) || name.starts_with('.') {
+        return None;
+    }
+
+    let path = dir.join(format!("{}.toml", name));
+    if path.is_file() {
+        return Some(ThemeSource::File(path));
+    }
+    BUILTIN
+        .iter()
+        .find(|(builtin, _)| *builtin == name)
+        .map(|(_, contents)| ThemeSource::Builtin(contents))
+}
+
+/// Lists all available theme names.
+///
+/// # Arguments
+/// * `dir` - User themes directory (see `themes_dir`)
+///
+/// # Returns
+/// The built-in names in menu order, followed by the user themes that don't
+/// replace a built-in one, sorted
+pub fn names(dir: &Path) -> Vec<String> {
+    let mut names: Vec<String> = BUILTIN.iter().map(|(name, _)| name.to_string()).collect();
+
+    let mut user: Vec<String> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            if path.extension()? != "toml" {
+                return None;
+            }
+            let name = path.file_stem()?.to_str()?.to_string();
+            (!name.starts_with('.')).then_some(name)
+        })
+        .filter(|name| !names.contains(name))
+        .collect();
+    user.sort();
+
+    names.append(&mut user);
+    names
+}
+
+/// Returns the theme after `current` in `names`, wrapping around.
+///
+/// An unknown current theme moves to the first one.
+pub fn next<'a>(current: &str, names: &'a [String]) -> Option<&'a str> {
+    let index = names
+        .iter()
+        .position(|name| name == current)
+        .map_or(0, |i| (i + 1) % names.len());
+    names.get(index).map(String::as_str)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{COLOR_KEYS, Config};
+
+    #[test]
+    fn test_builtin_themes_are_valid() {
+        for (name, contents) in BUILTIN {
+            let config: Config =
+                toml::from_str(contents).unwrap_or_else(|e| panic!("theme {}: {}", name, e));
+            assert!(config.validate().is_empty(), "theme {}", name);
+            // Every theme sets every color, so switching leaves nothing behind
+            let table: toml::Table = toml::from_str(contents).unwrap();
+            let colors = table["colors"].as_table().unwrap();
+            for key in COLOR_KEYS {
+                assert!(colors.contains_key(key), "theme {} lacks {}", name, key);
+            }
+        }
+    }
+
+    #[test]
+    fn test_default_theme_matches_defaults() {
+        let mut config: Config = toml::from_str(BUILTIN[0].1).unwrap();
+        config.colors.resolve();
+        let default = Config::default();
+        for key in COLOR_KEYS {
+            assert_eq!(config.colors.get(key), default.colors.get(key), "{}", key);
+        }
+    }
+
+    #[test]
+    fn test_find_and_list_user_themes() {
+        let dir = std::env::temp_dir().join("chronomatrix_test_themes");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("nord.toml"), "[colors]\nclock_bg = \"#000000\"\n").unwrap();
+        fs::write(dir.join("autumn.toml"), "").unwrap();
+        fs::write(dir.join("notes.txt"), "").unwrap();
+
+        assert_eq!(
+            find("nord", &dir),
+            Some(ThemeSource::File(dir.join("nord.toml")))
+        );
+        assert!(matches!(
+            find("gruvbox", &dir),
+            Some(ThemeSource::Builtin(_))
+        ));
+        assert_eq!(find("missing", &dir), None);
+        assert_eq!(find("../nord", &dir), None);
+
+        let names = names(&dir);
+        assert_eq!(names.len(), BUILTIN.len() + 1);
+        assert_eq!(names.last().map(String::as_str), Some("autumn"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_next_wraps_around() {
+        let names: Vec<String> = ["a", "b", "c"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(next("a", &names), Some("b"));
+        assert_eq!(next("c", &names), Some("a"));
+        assert_eq!(next("unknown", &names), Some("a"));
+        assert_eq!(next("a", &[]), None);
+    }
+
+    #[test]
+    fn test_accent_colors() {
+        assert_eq!(SystemStyle::default().accent_colors(), None);
+
+        let system = SystemStyle {
+            dark: true,
+            accent: Some("#3584e4".to_string()),
+        };
+        let table = system.accent_colors().unwrap();
+        let colors = table["colors"].as_table().unwrap();
+        assert_eq!(colors["clock_hand_color"].as_str(), Some("#3584e4"));
+        assert_eq!(colors["separator_color"].as_str(), Some("#3584e4"));
+    }
+}
@@ -0,0 +1,393 @@
+//! Window behavior that can't be expressed in CSS.
+//!
+//! - `mode` shows the window normally, fullscreen, or as a desktop widget:
+//!   anchored to a screen corner or edge, below other windows, on every
+//!   workspace, out of the taskbar and click-through. Widgets use layer-shell
+//!   on Wayland (see `layer_shell`) and EWMH hints on X11 (see `x11`)
+//! - `transparent` adds the `transparent` style class, which the stylesheet
+//!   uses to drop the window background and shadow so only the clock is drawn
+//! - `click_through` empties the surface's input region, so pointer events
+//!   reach whatever is below the clock and it floats over the desktop like a
+//!   widget. A click-through window can still be controlled through
+//!   `app.*` actions and the IPC socket.
+//! - The undecorated window can be resized from its edges, and its geometry
+//!   is saved on exit and restored on launch (see `window_state`)
+//!
+//! Entering or leaving widget mode changes how the window is created, so it
+//! takes effect on the next start; everything else is applied on reload.
+
+use gtk4::prelude::*;
+use gtk4::{ApplicationWindow, cairo, gdk, glib};
+use std::cell::RefCell;
+use std::path::Path;
+use std::rc::Rc;
+
+use crate::config::{Config, WindowConfig, WindowMode};
+use crate::window_state::WindowState;
+
+/// Style class set on the window while `transparent` is enabled.
+const TRANSPARENT_CLASS: &str = "transparent";
+
+/// Largest input region side GDK passes through unchanged on every backend
+/// (X11 shape rectangles are 16-bit). Regions are clipped to the surface, so
+/// this restores input on the whole window.
+const FULL_INPUT_SIZE: i32 = i16::MAX as i32;
+
+/// Width of the band along the window's edges that resizes it, in pixels.
+const RESIZE_BORDER: f64 = 8.0;
+
+/// Sets up the window for its configured mode before it is shown.
+///
+/// The input region and X11 hints live on the GDK surface, which only
+/// exists once the window is realized, so they're applied from there.
+///
+/// # Arguments
+/// * `window` - The main window, not yet realized
+/// * `config` - The shared configuration
+/// * `state` - Geometry saved by the previous run
+///
+/// # Returns
+/// The mode that took effect: `Normal` when widget mode was requested but
+/// no widget backend is available
+pub fn setup(
+    window: &ApplicationWindow,
+    config: &Rc<RefCell<Config>>,
+    state: &WindowState,
+) -> WindowMode {
+    let window_config = config.borrow().window.clone();
+    let monitor = state
+        .monitor
+        .as_deref()
+        .and_then(|connector| find_monitor(&WidgetExt::display(window), connector));
+
+    window.set_default_size(state.width, state.height);
+    let mode = match window_config.mode {
+        WindowMode::Normal => {
+            if state.maximized {
+                window.maximize();
+            }
+            if let (Some(monitor), Some(position)) = (monitor, state.position()) {
+                restore_position(window, &monitor, position);
+            }
+            WindowMode::Normal
+        }
+        WindowMode::Fullscreen => {
+            match monitor {
+                Some(monitor) => window.fullscreen_on_monitor(&monitor),
+                None => window.fullscreen(),
+            }
+            WindowMode::Fullscreen
+        }
+        WindowMode::Widget if setup_widget(window, config) => WindowMode::Widget,
+        WindowMode::Widget => WindowMode::Normal,
+    };
+    apply_appearance(window, mode, &window_config);
+    setup_resize_handles(window);
+
+    window.connect_realize(glib::clone!(
+        #[strong]
+        config,
+        move |window| apply_appearance(window, mode, &config.borrow().window)
+    ));
+    mode
+}
+
+/// Applies changed window settings to a running window.
+///
+/// The window stays a widget or a regular window until a restart, whatever
+/// `new` says, so only the settings that fit `startup_mode` are applied.
+///
+/// # Arguments
+/// * `window` - The main window
+/// * `startup_mode` - The mode the window was set up with by `setup`
+/// * `old` - The `[window]` section currently applied
+/// * `new` - The `[window]` section to apply
+pub fn apply(
+    window: &ApplicationWindow,
+    startup_mode: WindowMode,
+    old: &WindowConfig,
+    new: &WindowConfig,
+) {
+    if (old.mode == WindowMode::Widget) != (new.mode == WindowMode::Widget) {
+        eprintln!("Warning: window.mode changes to or from \"widget\" take effect after a restart");
+    }
+
+    if startup_mode == WindowMode::Widget {
+        if new.mode == WindowMode::Widget {
+            place_widget(window, new);
+        }
+    } else if new.mode != WindowMode::Widget && old.mode != new.mode {
+        // Only follow the mode when it changes, so toggling fullscreen by hand sticks
+        if new.mode == WindowMode::Fullscreen {
+            window.fullscreen();
+        } else {
+            window.unfullscreen();
+        }
+    }
+    apply_appearance(window, startup_mode, new);
+}
+
+/// Updates transparency and input passthrough from the window settings.
+///
+/// # Arguments
+/// * `window` - The main window
+/// * `mode` - The mode the window was set up with; widgets are always
+///   click-through
+/// * `config` - The `[window]` section to apply
+fn apply_appearance(window: &ApplicationWindow, mode: WindowMode, config: &WindowConfig) {
+    if config.transparent {
+        window.add_css_class(TRANSPARENT_CLASS);
+    } else {
+        window.remove_css_class(TRANSPARENT_CLASS);
+    }
+
+    if let Some(surface) = window.surface() {
+        let click_through =
+            mode == WindowMode::Widget || (config.transparent && config.click_through);
+        let region = if click_through {
+            cairo::Region::create()
+        } else {
+            cairo::Region::create_rectangle(&cairo::RectangleInt::new(
+                0,
+                0,
+                FULL_INPUT_SIZE,
+                FULL_INPUT_SIZE,
+            ))
+        };
+        surface.set_input_region(&region);
+    }
+}
+
+/// Saves the window's geometry for the next launch.
+///
+/// # Arguments
+/// * `window` - The main window, still realized
+/// * `mode` - The current window mode; widgets don't save their position
+/// * `path` - The state file
+pub fn save_state(window: &ApplicationWindow, mode: WindowMode, path: &Path) {
+    // The default size tracks the last unmaximized, non-fullscreen size
+    let (width, height) = window.default_size();
+    let mut state = WindowState {
+        width,
+        height,
+        maximized: window.is_maximized(),
+        ..WindowState::default()
+    };
+
+    if let Some(surface) = window.surface()
+        && let Some(monitor) = surface.display().monitor_at_surface(&surface)
+    {
+        state.monitor = monitor.connector().map(String::from);
+
+        #[cfg(feature = "x11")]
+        if mode == WindowMode::Normal
+            && !window.is_maximized()
+            && !window.is_fullscreen()
+            && crate::x11::is_x11(&surface.display())
+            && let Some((x, y)) = crate::x11::position(&surface)
+        {
+            let area = monitor.geometry();
+            state.x = Some(x - area.x());
+            state.y = Some(y - area.y());
+        }
+    }
+    #[cfg(not(feature = "x11"))]
+    let _ = mode;
+
+    if let Err(e) = state.save(path) {
+        eprintln!("Warning: Could not save window state {:?}: {}", path, e);
+    }
+}
+
+/// Finds a connected monitor by connector name, such as `DP-1`.
+fn find_monitor(display: &gdk::Display, connector: &str) -> Option<gdk::Monitor> {
+    let monitors = display.monitors();
+    (0..monitors.n_items())
+        .filter_map(|i| monitors.item(i).and_downcast::<gdk::Monitor>())
+        .find(|monitor| monitor.connector().as_deref() == Some(connector))
+}
+
+/// Moves the window back to its saved position once it is shown.
+///
+/// Only X11 lets applications place their windows; elsewhere the window
+/// manager decides.
+fn restore_position(window: &ApplicationWindow, monitor: &gdk::Monitor, position: (i32, i32)) {
+    #[cfg(feature = "x11")]
+    if crate::x11::is_x11(&monitor.display()) {
+        let area = monitor.geometry();
+        let (x, y) = (area.x() + position.0, area.y() + position.1);
+        window.connect_map(move |window| {
+            if let Some(surface) = window.surface() {
+                crate::x11::move_to(&surface, x, y);
+            }
+        });
+    }
+
+    #[cfg(not(feature = "x11"))]
+    let _ = (window, monitor, position);
+}
+
+/// Lets the undecorated window be resized by dragging its edges.
+///
+/// Presses near an edge start a compositor-driven resize; all other presses
+/// pass through to the window content, which moves the window.
+fn setup_resize_handles(window: &ApplicationWindow) {
+    let motion = gtk4::EventControllerMotion::new();
+    motion.connect_motion(glib::clone!(
+        #[weak]
+        window,
+        move |_, x, y| {
+            let cursor = resize_edge(&window, x, y).map(cursor_name);
+            window.set_cursor_from_name(cursor);
+        }
+    ));
+    window.add_controller(motion);
+
+    let click = gtk4::GestureClick::new();
+    click.set_button(gdk::BUTTON_PRIMARY);
+    click.set_propagation_phase(gtk4::PropagationPhase::Capture);
+    click.connect_pressed(glib::clone!(
+        #[weak]
+        window,
+        move |gesture, _, x, y| {
+            let Some(edge) = resize_edge(&window, x, y) else {
+                return;
+            };
+            let (Some(surface), Some(device)) = (window.surface(), gesture.device()) else {
+                return;
+            };
+            let Ok(toplevel) = surface.downcast::<gdk::Toplevel>() else {
+                return;
+            };
+
+            gesture.set_state(gtk4::EventSequenceState::Claimed);
+            let (offset_x, offset_y) = window.surface_transform();
+            toplevel.begin_resize(
+                edge,
+                Some(&device),
+                gdk::BUTTON_PRIMARY as i32,
+                x + offset_x,
+                y + offset_y,
+                gesture.current_event_time(),
+            );
+        }
+    ));
+    window.add_controller(click);
+}
+
+/// Returns the edge a point in the window would resize, if any.
+fn resize_edge(window: &ApplicationWindow, x: f64, y: f64) -> Option<gdk::SurfaceEdge> {
+    if window.is_maximized() || window.is_fullscreen() {
+        return None;
+    }
+
+    let (width, height) = (f64::from(window.width()), f64::from(window.height()));
+    let left = x < RESIZE_BORDER;
+    let right = x > width - RESIZE_BORDER;
+    let top = y < RESIZE_BORDER;
+    let bottom = y > height - RESIZE_BORDER;
+
+    match (left, right, top, bottom) {
+        (true, _, true, _) => Some(gdk::SurfaceEdge::NorthWest),
+        (_, true, true, _) => Some(gdk::SurfaceEdge::NorthEast),
+        (true, _, _, true) => Some(gdk::SurfaceEdge::SouthWest),
+        (_, true, _, true) => Some(gdk::SurfaceEdge::SouthEast),
+        (true, _, _, _) => Some(gdk::SurfaceEdge::West),
+        (_, true, _, _) => Some(gdk::SurfaceEdge::East),
+        (_, _, true, _) => Some(gdk::SurfaceEdge::North),
+        (_, _, _, true) => Some(gdk::SurfaceEdge::South),
+        _ => None,
+    }
+}
+
+/// Returns the cursor shown over a resize edge.
+fn cursor_name(edge: gdk::SurfaceEdge) -> &'static str {
+    match edge {
+        gdk::SurfaceEdge::NorthWest => "nw-resize",
+        gdk::SurfaceEdge::North => "n-resize",
+        gdk::SurfaceEdge::NorthEast => "ne-resize",
+        gdk::SurfaceEdge::West => "w-resize",
+        gdk::SurfaceEdge::East => "e-resize",
+        gdk::SurfaceEdge::SouthWest => "sw-resize",
+        gdk::SurfaceEdge::South => "s-resize",
+        _ => "se-resize",
+    }
+}
+
+/// Turns the window into a desktop widget with whichever backend is available.
+///
+/// # Returns
+/// Whether a backend was set up; the window stays a normal window otherwise
+fn setup_widget(window: &ApplicationWindow, config: &Rc<RefCell<Config>>) -> bool {
+    #[cfg(feature = "layer-shell")]
+    if crate::layer_shell::init(window) {
+        place_widget(window, &config.borrow().window);
+        return true;
+    }
+
+    #[cfg(feature = "x11")]
+    if crate::x11::is_x11(&WidgetExt::display(window)) {
+        // The window manager positions new windows, so move it once shown
+        // and whenever its size changes
+        let placed_size = Rc::new(std::cell::Cell::new((0, 0)));
+        window.connect_realize(glib::clone!(
+            #[strong]
+            config,
+            move |window| {
+                let Some(surface) = window.surface() else {
+                    return;
+                };
+                crate::x11::set_widget_hints(&surface);
+                surface.connect_layout(glib::clone!(
+                    #[weak]
+                    window,
+                    #[strong]
+                    config,
+                    #[strong]
+                    placed_size,
+                    move |_, width, height| {
+                        if placed_size.replace((width, height)) != (width, height) {
+                            place_widget(&window, &config.borrow().window);
+                        }
+                    }
+                ));
+            }
+        ));
+        window.connect_map(glib::clone!(
+            #[strong]
+            config,
+            move |window| {
+                if let Some(surface) = window.surface() {
+                    crate::x11::keep_below(&surface);
+                }
+                place_widget(window, &config.borrow().window);
+            }
+        ));
+        return true;
+    }
+
+    let _ = (window, config);
+    eprintln!(
+        "Warning: Widget mode needs layer-shell on Wayland or an X11 session; showing a normal window"
+    );
+    false
+}
+
+/// Moves a widget to its anchor.
+fn place_widget(window: &ApplicationWindow, config: &WindowConfig) {
+    #[cfg(feature = "layer-shell")]
+    if crate::layer_shell::is_layer_window(window) {
+        crate::layer_shell::set_anchor(window, config.anchor, config.margin);
+    }
+
+    #[cfg(feature = "x11")]
+    if let Some(surface) = window.surface()
+        && surface.is_mapped()
+        && crate::x11::is_x11(&surface.display())
+    {
+        crate::x11::move_to_anchor(&surface, config.anchor, config.margin);
+    }
+
+    #[cfg(not(any(feature = "layer-shell", feature = "x11")))]
+    let _ = (window, config);
+}
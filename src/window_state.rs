@@ -0,0 +1,158 @@
+//! Window geometry remembered between runs.
+//!
+//! The state is saved to `$XDG_STATE_HOME/chronomatrix/window-state.toml`
+//! (usually `~/.local/state`) when the app exits and restored on launch:
+//! - Size and maximized state, on every platform
+//! - The monitor, by connector name such as `DP-1`
+//! - The position on that monitor, on X11 only, since Wayland doesn't let
+//!   applications place their windows
+//!
+//! A missing or unreadable file just means default geometry.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Window width used before any state has been saved.
+pub const DEFAULT_WIDTH: i32 = 1200;
+/// Window height used before any state has been saved.
+pub const DEFAULT_HEIGHT: i32 = 400;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WindowState {
+    /// Unmaximized width in pixels
+    pub width: i32,
+    /// Unmaximized height in pixels
+    pub height: i32,
+    pub maximized: bool,
+    /// Connector name of the monitor the window was on
+    pub monitor: Option<String>,
+    /// Horizontal offset from the monitor's left edge (X11 only)
+    pub x: Option<i32>,
+    /// Vertical offset from the monitor's top edge (X11 only)
+    pub y: Option<i32>,
+}
+
+impl Default for WindowState {
+    fn default() -> Self {
+        WindowState {
+            width: DEFAULT_WIDTH,
+            height: DEFAULT_HEIGHT,
+            maximized: false,
+            monitor: None,
+            x: None,
+            y: None,
+        }
+    }
+}
+
+impl WindowState {
+    /// Gets the default state file path.
+    ///
+    /// # Returns
+    /// `$XDG_STATE_HOME/chronomatrix/window-state.toml`, falling back to the
+    /// local data directory where there is no state directory
+    pub fn default_path() -> PathBuf {
+        let mut path = dirs::state_dir()
+            .or_else(dirs::data_local_dir)
+            .unwrap_or_else(|| PathBuf::from("."));
+        path.push("chronomatrix");
+        path.push("window-state.toml");
+        path
+    }
+
+    /// Loads the saved state, falling back to defaults.
+    ///
+    /// A missing file is expected on first launch; any other problem is
+    /// reported as a warning.
+    ///
+    /// # Arguments
+    /// * `path` - The state file
+    pub fn load(path: &Path) -> Self {
+        let contents = match fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Self::default(),
+            Err(e) => {
+                eprintln!("Warning: Could not read window state {:?}: {}", path, e);
+                return Self::default();
+            }
+        };
+
+        match toml::from_str::<WindowState>(&contents) {
+            Ok(state) if state.width > 0 && state.height > 0 => state,
+            Ok(_) => Self::default(),
+            Err(e) => {
+                eprintln!("Warning: Ignoring window state {:?}: {}", path, e.message());
+                Self::default()
+            }
+        }
+    }
+
+    /// Writes the state, creating its directory if needed.
+    ///
+    /// # Arguments
+    /// * `path` - The state file
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let contents = toml::to_string(self).map_err(io::Error::other)?;
+        fs::write(path, contents)
+    }
+
+    /// Returns the saved position, if one was recorded.
+    pub fn position(&self) -> Option<(i32, i32)> {
+        self.x.zip(self.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = std::env::temp_dir().join("chronomatrix_test_window_state");
+        let path = dir.join("nested").join("window-state.toml");
+        let _ = fs::remove_dir_all(&dir);
+
+        let state = WindowState {
+            width: 800,
+            height: 300,
+            maximized: false,
+            monitor: Some("DP-1".to_string()),
+            x: Some(40),
+            y: Some(60),
+        };
+        state.save(&path).unwrap();
+        assert_eq!(WindowState::load(&path), state);
+        assert_eq!(WindowState::load(&path).position(), Some((40, 60)));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_load_falls_back_to_defaults() {
+        let dir = std::env::temp_dir().join("chronomatrix_test_window_state_bad");
+        let _ = fs::create_dir_all(&dir);
+        let path = dir.join("window-state.toml");
+
+        assert_eq!(WindowState::load(&path), WindowState::default());
+
+        fs::write(&path, "width = \"wide\"\n").unwrap();
+        assert_eq!(WindowState::load(&path), WindowState::default());
+
+        fs::write(&path, "width = 0\nheight = 200\n").unwrap();
+        assert_eq!(WindowState::load(&path), WindowState::default());
+
+        // Missing keys keep their defaults; a position needs both coordinates
+        fs::write(&path, "width = 640\nx = 10\n").unwrap();
+        let state = WindowState::load(&path);
+        assert_eq!((state.width, state.height), (640, DEFAULT_HEIGHT));
+        assert_eq!(state.position(), None);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
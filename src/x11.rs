@@ -0,0 +1,351 @@
+//! Widget mode on X11 through EWMH window hints.
+//!
+//! GTK 4 dropped most of its X11 window-management API, so the hints are set
+//! directly: the window is typed as a desktop window, kept below others and
+//! on every workspace, hidden from the taskbar and pager, and moved to its
+//! anchor with Xlib. The same calls read and restore the position of normal
+//! windows between runs. Only the handful of functions used here are bound,
+//! and they are loaded at runtime: without Xlib, or with a GTK built without
+//! X11 support, X11 sessions get a normal window instead of a widget.
+
+use glib::translate::ToGlibPtr;
+use gtk4::prelude::*;
+use gtk4::{gdk, glib};
+use libloading::Library;
+use std::ffi::{CString, c_char, c_int, c_long, c_uchar, c_ulong, c_void};
+use std::sync::OnceLock;
+
+use crate::config::Anchor;
+
+type XDisplay = c_void;
+type XWindow = c_ulong;
+type Atom = c_ulong;
+
+const XA_ATOM: Atom = 4;
+const PROP_MODE_REPLACE: c_int = 0;
+const CLIENT_MESSAGE: c_int = 33;
+const SUBSTRUCTURE_NOTIFY_MASK: c_long = 1 << 19;
+const SUBSTRUCTURE_REDIRECT_MASK: c_long = 1 << 20;
+const NET_WM_STATE_ADD: c_long = 1;
+/// `_NET_WM_DESKTOP` value meaning "on all desktops"
+const ALL_DESKTOPS: u32 = 0xFFFF_FFFF;
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct XClientMessageEvent {
+    type_: c_int,
+    serial: c_ulong,
+    send_event: c_int,
+    display: *mut XDisplay,
+    window: XWindow,
+    message_type: Atom,
+    format: c_int,
+    data: [c_long; 5],
+}
+
+/// Xlib's `XEvent` union, padded to its full size.
+#[repr(C)]
+union XEvent {
+    client_message: XClientMessageEvent,
+    pad: [c_long; 24],
+}
+
+/// Shared library providing the `X*` functions
+const LIBRARY: &str = "libX11.so.6";
+
+/// The GDK X11 backend and Xlib functions used here.
+struct Xlib {
+    get_xdisplay: unsafe extern "C" fn(*mut gdk::ffi::GdkDisplay) -> *mut XDisplay,
+    get_xid: unsafe extern "C" fn(*mut gdk::ffi::GdkSurface) -> XWindow,
+    set_skip_taskbar_hint: unsafe extern "C" fn(*mut gdk::ffi::GdkSurface, glib::ffi::gboolean),
+    set_skip_pager_hint: unsafe extern "C" fn(*mut gdk::ffi::GdkSurface, glib::ffi::gboolean),
+    move_to_desktop: unsafe extern "C" fn(*mut gdk::ffi::GdkSurface, u32),
+    intern_atom: unsafe extern "C" fn(*mut XDisplay, *const c_char, c_int) -> Atom,
+    change_property: unsafe extern "C" fn(
+        *mut XDisplay,
+        XWindow,
+        Atom,
+        Atom,
+        c_int,
+        c_int,
+        *const c_uchar,
+        c_int,
+    ) -> c_int,
+    send_event: unsafe extern "C" fn(*mut XDisplay, XWindow, c_int, c_long, *mut XEvent) -> c_int,
+    move_window: unsafe extern "C" fn(*mut XDisplay, XWindow, c_int, c_int) -> c_int,
+    translate_coordinates: unsafe extern "C" fn(
+        *mut XDisplay,
+        XWindow,
+        XWindow,
+        c_int,
+        c_int,
+        *mut c_int,
+        *mut c_int,
+        *mut XWindow,
+    ) -> c_int,
+    default_root_window: unsafe extern "C" fn(*mut XDisplay) -> XWindow,
+    /// Keeps the functions above loaded
+    _gtk: Library,
+    /// Keeps the functions above loaded
+    _xlib: Library,
+}
+
+impl Xlib {
+    fn load() -> Result<Self, libloading::Error> {
+        // SAFETY: neither library has initialization side effects, and each
+        // symbol is looked up with the signature from its header. The GDK
+        // functions come from the GTK already loaded into this process.
+        unsafe {
+            let gtk: Library = libloading::os::unix::Library::this().into();
+            let xlib = Library::new(LIBRARY)?;
+            Ok(Self {
+                get_xdisplay: *gtk.get(b"gdk_x11_display_get_xdisplay\0")?,
+                get_xid: *gtk.get(b"gdk_x11_surface_get_xid\0")?,
+                set_skip_taskbar_hint: *gtk.get(b"gdk_x11_surface_set_skip_taskbar_hint\0")?,
+                set_skip_pager_hint: *gtk.get(b"gdk_x11_surface_set_skip_pager_hint\0")?,
+                move_to_desktop: *gtk.get(b"gdk_x11_surface_move_to_desktop\0")?,
+                intern_atom: *xlib.get(b"XInternAtom\0")?,
+                change_property: *xlib.get(b"XChangeProperty\0")?,
+                send_event: *xlib.get(b"XSendEvent\0")?,
+                move_window: *xlib.get(b"XMoveWindow\0")?,
+                translate_coordinates: *xlib.get(b"XTranslateCoordinates\0")?,
+                default_root_window: *xlib.get(b"XDefaultRootWindow\0")?,
+                _gtk: gtk,
+                _xlib: xlib,
+            })
+        }
+    }
+}
+
+/// Returns the X11 functions, loading them on first use.
+///
+/// # Returns
+/// `None` if Xlib or GDK's X11 backend is missing, which is reported once
+fn xlib() -> Option<&'static Xlib> {
+    static XLIB: OnceLock<Option<Xlib>> = OnceLock::new();
+    XLIB.get_or_init(|| {
+        Xlib::load()
+            .map_err(|e| eprintln!("Warning: Could not load the X11 functions: {}", e))
+            .ok()
+    })
+    .as_ref()
+}
+
+/// Returns whether a display is an X11 connection the functions here can
+/// drive.
+pub fn is_x11(display: &gdk::Display) -> bool {
+    display.type_().name() == "GdkX11Display" && xlib().is_some()
+}
+
+/// An X11 surface with its Xlib handles.
+struct Handles {
+    lib: &'static Xlib,
+    display: *mut XDisplay,
+    xid: XWindow,
+    surface: *mut gdk::ffi::GdkSurface,
+}
+
+impl Handles {
+    fn atom(&self, name: &str) -> Atom {
+        let name = CString::new(name).unwrap();
+        // SAFETY: the display is a live Xlib connection owned by GDK
+        unsafe { (self.lib.intern_atom)(self.display, name.as_ptr(), 0) }
+    }
+}
+
+/// Returns the Xlib handles of a surface.
+///
+/// # Returns
+/// `None` if the X11 functions couldn't be loaded
+fn handles(surface: &gdk::Surface) -> Option<Handles> {
+    let lib = xlib()?;
+    let surface_ptr: *mut gdk::ffi::GdkSurface = surface.to_glib_none().0;
+    // SAFETY: callers only pass surfaces of an X11 display
+    unsafe {
+        Some(Handles {
+            lib,
+            display: (lib.get_xdisplay)(surface.display().to_glib_none().0),
+            xid: (lib.get_xid)(surface_ptr),
+            surface: surface_ptr,
+        })
+    }
+}
+
+/// Sets the hints that must be in place before the window is mapped.
+///
+/// # Arguments
+/// * `surface` - The realized, not yet mapped surface
+pub fn set_widget_hints(surface: &gdk::Surface) {
+    let Some(x) = handles(surface) else {
+        return;
+    };
+    let window_type = [x.atom("_NET_WM_WINDOW_TYPE_DESKTOP")];
+
+    // SAFETY: the handles belong to a live X11 surface
+    unsafe {
+        (x.lib.change_property)(
+            x.display,
+            x.xid,
+            x.atom("_NET_WM_WINDOW_TYPE"),
+            XA_ATOM,
+            32,
+            PROP_MODE_REPLACE,
+            window_type.as_ptr().cast(),
+            1,
+        );
+        (x.lib.set_skip_taskbar_hint)(x.surface, glib::ffi::GTRUE);
+        (x.lib.set_skip_pager_hint)(x.surface, glib::ffi::GTRUE);
+    }
+}
+
+/// Asks the window manager to keep a mapped window below others and on
+/// every workspace.
+///
+/// # Arguments
+/// * `surface` - The mapped surface
+pub fn keep_below(surface: &gdk::Surface) {
+    let Some(x) = handles(surface) else {
+        return;
+    };
+    let mut event = XEvent {
+        client_message: XClientMessageEvent {
+            type_: CLIENT_MESSAGE,
+            serial: 0,
+            send_event: 1,
+            display: x.display,
+            window: x.xid,
+            message_type: x.atom("_NET_WM_STATE"),
+            format: 32,
+            data: [
+                NET_WM_STATE_ADD,
+                x.atom("_NET_WM_STATE_BELOW") as c_long,
+                x.atom("_NET_WM_STATE_STICKY") as c_long,
+                1, // Source indication: normal application
+                0,
+            ],
+        },
+    };
+
+    // SAFETY: the handles belong to a live X11 surface and the event is a
+    // fully initialized client message
+    unsafe {
+        (x.lib.send_event)(
+            x.display,
+            (x.lib.default_root_window)(x.display),
+            0,
+            SUBSTRUCTURE_NOTIFY_MASK | SUBSTRUCTURE_REDIRECT_MASK,
+            &mut event,
+        );
+        (x.lib.move_to_desktop)(x.surface, ALL_DESKTOPS);
+    }
+}
+
+/// Moves a mapped window to its anchor on the monitor it is on.
+///
+/// # Arguments
+/// * `surface` - The mapped surface
+/// * `anchor` - Where to place the window
+/// * `margin` - Distance from the attached edges in pixels
+pub fn move_to_anchor(surface: &gdk::Surface, anchor: Anchor, margin: i32) {
+    let Some(monitor) = surface.display().monitor_at_surface(surface) else {
+        return;
+    };
+    let size = (surface.width(), surface.height());
+    let (x, y) = anchor_position(&monitor.geometry(), size, anchor, margin);
+    move_to(surface, x, y);
+}
+
+/// Returns where a window's top-left corner goes to sit at an anchor.
+///
+/// # Arguments
+/// * `area` - The monitor geometry
+/// * `size` - The window's width and height
+/// * `anchor` - Where to place the window
+/// * `margin` - Distance from the attached edges in pixels
+///
+/// # Returns
+/// The position in the same coordinates as `area`
+fn anchor_position(
+    area: &gdk::Rectangle,
+    size: (i32, i32),
+    anchor: Anchor,
+    margin: i32,
+) -> (i32, i32) {
+    let edges = anchor.edges();
+    let place = |start: i32, length: i32, size: i32, near: bool, far: bool| {
+        if near {
+            start + margin
+        } else if far {
+            start + length - size - margin
+        } else {
+            start + (length - size) / 2
+        }
+    };
+    (
+        place(area.x(), area.width(), size.0, edges.left, edges.right),
+        place(area.y(), area.height(), size.1, edges.top, edges.bottom),
+    )
+}
+
+/// Moves a mapped window so its top-left corner is at a screen position.
+///
+/// # Arguments
+/// * `surface` - The mapped surface
+/// * `x`, `y` - Position in logical pixels, like monitor geometry
+pub fn move_to(surface: &gdk::Surface, x: i32, y: i32) {
+    // Monitor geometry is in logical pixels, Xlib works in device pixels
+    let scale = surface.scale_factor();
+    let Some(handles) = handles(surface) else {
+        return;
+    };
+    // SAFETY: the handles belong to a live X11 surface
+    unsafe {
+        (handles.lib.move_window)(handles.display, handles.xid, x * scale, y * scale);
+    }
+}
+
+/// Returns the screen position of a window's top-left corner.
+///
+/// # Returns
+/// The position in logical pixels, like monitor geometry, or `None` if the
+/// X11 functions couldn't be loaded
+pub fn position(surface: &gdk::Surface) -> Option<(i32, i32)> {
+    let handles = handles(surface)?;
+    let (mut x, mut y, mut child) = (0, 0, 0);
+    // SAFETY: the handles belong to a live X11 surface and the out
+    // parameters point to locals
+    unsafe {
+        (handles.lib.translate_coordinates)(
+            handles.display,
+            handles.xid,
+            (handles.lib.default_root_window)(handles.display),
+            0,
+            0,
+            &mut x,
+            &mut y,
+            &mut child,
+        );
+    }
+    let scale = surface.scale_factor();
+    Some((x / scale, y / scale))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_anchor_position() {
+        // A second monitor to the right of a 1920-pixel one
+        let area = gdk::Rectangle::new(1920, 0, 2560, 1440);
+        let size = (400, 200);
+        let at = |anchor| anchor_position(&area, size, anchor, 32);
+
+        assert_eq!(at(Anchor::TopLeft), (1952, 32));
+        assert_eq!(at(Anchor::TopRight), (1920 + 2560 - 400 - 32, 32));
+        assert_eq!(at(Anchor::BottomLeft), (1952, 1440 - 200 - 32));
+        assert_eq!(at(Anchor::Center), (1920 + 1080, 620));
+        assert_eq!(at(Anchor::Top), (1920 + 1080, 32));
+        assert_eq!(at(Anchor::Right), (1920 + 2560 - 400 - 32, 620));
+    }
+}